@@ -0,0 +1,147 @@
+//! Derive macro for `hash_table_datastruct::record::TableRow`
+//!
+//! Use it through the `derive` feature of `hash_table_datastruct`, see the documentation of the
+//! `record` module there.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Implement `TableRow<V>` for a struct with named fields, for every `V` the field types can be
+/// converted to and from.
+///
+/// Field attributes:
+///
+/// - `#[table(rename = "key")]` stores the field in the column `key` instead of the field name,
+/// - `#[table(skip)]` leaves the field out of the table, it's set to its `Default` value when read.
+#[proc_macro_derive(TableRow, attributes(table))]
+pub fn derive_table_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A field stored in a column
+struct Column {
+    ident: syn::Ident,
+    ty: syn::Type,
+    key: String,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "TableRow can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "TableRow can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut columns = Vec::new();
+    let mut skipped = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.clone().expect("Fields are named");
+        let mut key = ident.to_string();
+        let mut skip = false;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("table"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    key = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename = \"...\"` or `skip`"))
+                }
+            })?;
+        }
+        if skip {
+            skipped.push(ident);
+        } else {
+            if let Some(other) = columns.iter().find(|column: &&Column| column.key == key) {
+                return Err(syn::Error::new_spanned(
+                    &field.ident,
+                    format!("column {key:?} is already used by field `{}`", other.ident),
+                ));
+            }
+            columns.push(Column {
+                ident,
+                ty: field.ty.clone(),
+                key,
+            });
+        }
+    }
+
+    let name = &input.ident;
+    let krate = quote!(::hash_table_datastruct);
+    let mut generics = input.generics.clone();
+    generics.params.push(syn::parse_quote!(__V));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut predicates: Vec<TokenStream2> = where_clause
+        .map(|clause| clause.predicates.iter().map(|p| quote!(#p)).collect())
+        .unwrap_or_default();
+    for Column { ty, .. } in &columns {
+        predicates.push(quote! {
+            #ty: #krate::record::ToTableValue<__V> + #krate::record::FromTableValue<__V>
+        });
+    }
+    for field in fields
+        .named
+        .iter()
+        .filter(|field| skipped.contains(field.ident.as_ref().expect("Fields are named")))
+    {
+        let ty = &field.ty;
+        predicates.push(quote!(#ty: ::core::default::Default));
+    }
+
+    let keys = columns.iter().map(|column| &column.key);
+    let idents = columns.iter().map(|column| &column.ident);
+    let reads = columns.iter().map(|Column { ident, ty, key }| {
+        quote! {
+            #ident: <#ty as #krate::record::FromTableValue<__V>>::from_table_value(
+                row.get(#key).ok_or(#krate::record::RecordError::MissingColumn(#key))?,
+            )
+            .ok_or(#krate::record::RecordError::InvalidValue(#key))?
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::record::TableRow<__V> for #name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            const COLUMNS: &'static [&'static str] = &[#(#keys),*];
+
+            fn to_row(&self) -> ::std::vec::Vec<__V> {
+                ::std::vec![
+                    #(#krate::record::ToTableValue::<__V>::to_table_value(&self.#idents)),*
+                ]
+            }
+
+            fn from_row<__K>(
+                row: #krate::row::borrowed::HashTableRowBorrowed<'_, __K, __V>,
+            ) -> ::core::result::Result<Self, #krate::record::RecordError>
+            where
+                __K: ::core::borrow::Borrow<str> + ::core::hash::Hash + ::core::cmp::Eq,
+            {
+                ::core::result::Result::Ok(Self {
+                    #(#reads,)*
+                    #(#skipped: ::core::default::Default::default(),)*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,299 @@
+//! Conversion between [`HashTable`] and Arrow [`RecordBatch`]
+//!
+//! Each column of the table becomes one Arrow array, in column-index order. Values are converted
+//! to arrays through the [`ArrowValue`] trait.
+//!
+//! ## Example
+//! ```
+//! # use hash_table_datastruct::HashTable;
+//! let table = HashTable::from_column_keys_and_rows(
+//!     ["x".to_owned(), "y".to_owned()],
+//!     [[1_i64, 2], [3, 4]],
+//! );
+//! let batch = table.to_record_batch().unwrap();
+//! assert_eq!(batch.num_columns(), 2);
+//! assert_eq!(batch.schema().field(0).name(), "x");
+//! let restored = HashTable::<String, i64>::from_record_batch(&batch).unwrap();
+//! assert_eq!(restored.get("y", 1), Some(&4));
+//! ```
+
+use std::sync::Arc;
+
+use arrow_array::{
+    cast::AsArray,
+    types::{
+        Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type,
+        UInt32Type, UInt64Type, UInt8Type,
+    },
+    Array, ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+    Int8Array, NullArray, RecordBatch, RecordBatchOptions, StringArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+use crate::{
+    value::{ToValue, Value},
+    HashTable,
+};
+
+/// Values that can be stored in Arrow arrays
+pub trait ArrowValue: Sized {
+    /// Build an array from the values of a column
+    fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError>;
+
+    /// Read the values of a column from an array
+    fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError>;
+}
+
+fn unexpected_type(expected: &str, array: &dyn Array) -> ArrowError {
+    ArrowError::CastError(format!(
+        "expected an array of {expected}, found {}",
+        array.data_type()
+    ))
+}
+
+fn unexpected_nulls(array: &dyn Array) -> ArrowError {
+    ArrowError::InvalidArgumentError(format!(
+        "array of {} has null values, use an `Option` value type to read it",
+        array.data_type()
+    ))
+}
+
+macro_rules! impl_arrow_primitive {
+    ($($t:ty => $array:ty, $arrow_type:ty);* $(;)?) => {$(
+        impl ArrowValue for $t {
+            fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError> {
+                Ok(Arc::new(<$array>::from_iter_values(values.iter().map(|v| **v))))
+            }
+
+            fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError> {
+                let array = array
+                    .as_primitive_opt::<$arrow_type>()
+                    .ok_or_else(|| unexpected_type(stringify!($t), array))?;
+                if array.null_count() > 0 {
+                    return Err(unexpected_nulls(array));
+                }
+                Ok(array.values().to_vec())
+            }
+        }
+
+        impl ArrowValue for Option<$t> {
+            fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError> {
+                Ok(Arc::new(<$array>::from_iter(values.iter().map(|v| **v))))
+            }
+
+            fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError> {
+                let array = array
+                    .as_primitive_opt::<$arrow_type>()
+                    .ok_or_else(|| unexpected_type(stringify!($t), array))?;
+                Ok(array.iter().collect())
+            }
+        }
+    )*};
+}
+
+impl_arrow_primitive! {
+    i8 => Int8Array, Int8Type;
+    i16 => Int16Array, Int16Type;
+    i32 => Int32Array, Int32Type;
+    i64 => Int64Array, Int64Type;
+    u8 => UInt8Array, UInt8Type;
+    u16 => UInt16Array, UInt16Type;
+    u32 => UInt32Array, UInt32Type;
+    u64 => UInt64Array, UInt64Type;
+    f32 => Float32Array, Float32Type;
+    f64 => Float64Array, Float64Type;
+}
+
+impl ArrowValue for bool {
+    fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(BooleanArray::from_iter(
+            values.iter().map(|v| Some(**v)),
+        )))
+    }
+
+    fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError> {
+        let array = array
+            .as_boolean_opt()
+            .ok_or_else(|| unexpected_type("bool", array))?;
+        if array.null_count() > 0 {
+            return Err(unexpected_nulls(array));
+        }
+        Ok(array.values().iter().collect())
+    }
+}
+
+impl ArrowValue for Option<bool> {
+    fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(BooleanArray::from_iter(
+            values.iter().map(|v| **v),
+        )))
+    }
+
+    fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError> {
+        let array = array
+            .as_boolean_opt()
+            .ok_or_else(|| unexpected_type("bool", array))?;
+        Ok(array.iter().collect())
+    }
+}
+
+/// Read an array of strings of either offset size
+fn strings_from_array(array: &dyn Array) -> Result<Vec<Option<String>>, ArrowError> {
+    match array.data_type() {
+        DataType::Utf8 => Ok(array
+            .as_string::<i32>()
+            .iter()
+            .map(|s| s.map(str::to_owned))
+            .collect()),
+        DataType::LargeUtf8 => Ok(array
+            .as_string::<i64>()
+            .iter()
+            .map(|s| s.map(str::to_owned))
+            .collect()),
+        _ => Err(unexpected_type("strings", array)),
+    }
+}
+
+impl ArrowValue for String {
+    fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(StringArray::from_iter_values(values)))
+    }
+
+    fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError> {
+        strings_from_array(array)?
+            .into_iter()
+            .map(|s| s.ok_or_else(|| unexpected_nulls(array)))
+            .collect()
+    }
+}
+
+impl ArrowValue for Option<String> {
+    fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(StringArray::from_iter(
+            values.iter().map(|v| v.as_deref()),
+        )))
+    }
+
+    fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError> {
+        strings_from_array(array)
+    }
+}
+
+/// A column of [`Value`]s is stored with the natural Arrow type of its values: `Int64` for
+/// integers, `Float64` for floats or a mix of integers and floats, `Boolean`, `Utf8`, or `Null` if
+/// all values are null. Null values are allowed in any column, other mixes of types are an error.
+impl ArrowValue for Value {
+    fn to_array(values: &[&Self]) -> Result<ArrayRef, ArrowError> {
+        let mut data_type = DataType::Null;
+        for value in values {
+            data_type = match (&data_type, value) {
+                (_, Value::Null) => data_type,
+                (DataType::Null | DataType::Int64, Value::Int(_)) => DataType::Int64,
+                (DataType::Null | DataType::Int64 | DataType::Float64, Value::Float(_))
+                | (DataType::Float64, Value::Int(_)) => DataType::Float64,
+                (DataType::Null | DataType::Boolean, Value::Bool(_)) => DataType::Boolean,
+                (DataType::Null | DataType::Utf8, Value::Str(_)) => DataType::Utf8,
+                (data_type, value) => {
+                    return Err(ArrowError::InvalidArgumentError(format!(
+                        "column of {data_type} has a value of type {}",
+                        value.type_name()
+                    )))
+                }
+            };
+        }
+        let array: ArrayRef = match data_type {
+            DataType::Int64 => Arc::new(Int64Array::from_iter(values.iter().map(|v| match v {
+                Value::Int(i) => Some(*i),
+                _ => None,
+            }))),
+            DataType::Float64 => {
+                Arc::new(Float64Array::from_iter(values.iter().map(|v| v.as_f64())))
+            }
+            DataType::Boolean => {
+                Arc::new(BooleanArray::from_iter(values.iter().map(|v| match v {
+                    Value::Bool(b) => Some(*b),
+                    _ => None,
+                })))
+            }
+            DataType::Utf8 => Arc::new(StringArray::from_iter(values.iter().map(|v| match v {
+                Value::Str(s) => Some(s.as_str()),
+                _ => None,
+            }))),
+            _ => Arc::new(NullArray::new(values.len())),
+        };
+        Ok(array)
+    }
+
+    fn from_array(array: &dyn Array) -> Result<Vec<Self>, ArrowError> {
+        fn values<T: Into<Value>>(iter: impl Iterator<Item = Option<T>>) -> Vec<Value> {
+            iter.map(Value::from).collect()
+        }
+        Ok(match array.data_type() {
+            DataType::Null => vec![Value::Null; array.len()],
+            DataType::Boolean => values(array.as_boolean().iter()),
+            DataType::Int8 => values(array.as_primitive::<Int8Type>().iter()),
+            DataType::Int16 => values(array.as_primitive::<Int16Type>().iter()),
+            DataType::Int32 => values(array.as_primitive::<Int32Type>().iter()),
+            DataType::Int64 => values(array.as_primitive::<Int64Type>().iter()),
+            DataType::UInt8 => values(array.as_primitive::<UInt8Type>().iter()),
+            DataType::UInt16 => values(array.as_primitive::<UInt16Type>().iter()),
+            DataType::UInt32 => values(array.as_primitive::<UInt32Type>().iter()),
+            DataType::UInt64 => array
+                .as_primitive::<UInt64Type>()
+                .iter()
+                .map(|v| v.to_value())
+                .collect(),
+            DataType::Float32 => values(array.as_primitive::<Float32Type>().iter()),
+            DataType::Float64 => values(array.as_primitive::<Float64Type>().iter()),
+            DataType::Utf8 | DataType::LargeUtf8 => values(strings_from_array(array)?.into_iter()),
+            other => {
+                return Err(ArrowError::NotYetImplemented(format!(
+                    "reading {other} arrays into values"
+                )))
+            }
+        })
+    }
+}
+
+impl<V: ArrowValue> HashTable<String, V> {
+    /// Convert the table into an Arrow record batch with one array per column in column-index
+    /// order.
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let mut fields = Vec::with_capacity(self.columns_len());
+        let mut columns = Vec::with_capacity(self.columns_len());
+        for column in self.iter_columns() {
+            let array = V::to_array(&column)?;
+            let nullable = array.null_count() > 0 || array.data_type() == &DataType::Null;
+            fields.push(Field::new(
+                column.column_key().clone(),
+                array.data_type().clone(),
+                nullable,
+            ));
+            columns.push(array);
+        }
+        RecordBatch::try_new_with_options(
+            Arc::new(Schema::new(fields)),
+            columns,
+            &RecordBatchOptions::new().with_row_count(Some(self.rows_len())),
+        )
+    }
+
+    /// Build a table from an Arrow record batch, with columns in the order of the batch schema.
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self, ArrowError> {
+        let schema = batch.schema();
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, array) in schema.fields().iter().zip(batch.columns()) {
+            if columns.iter().any(|(key, _)| key == field.name()) {
+                return Err(ArrowError::SchemaError(format!(
+                    "duplicate column `{}`",
+                    field.name()
+                )));
+            }
+            columns.push((field.name().clone(), V::from_array(array.as_ref())?));
+        }
+        let mut table = Self::with_columns_and_capacity([], batch.num_rows());
+        table.insert_columns(columns);
+        Ok(table)
+    }
+}
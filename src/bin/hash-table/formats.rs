@@ -0,0 +1,231 @@
+//! Reading and writing tables in the supported file formats
+
+use std::{fmt::Write as _, io};
+
+use clap::ValueEnum;
+use hash_table_datastruct::{table::serde_impls::hashtable_columns_map, value::Value, HashTable};
+use serde::Deserialize;
+use serde_json::{Map, Value as Json};
+
+pub type Table = HashTable<String, Value>;
+
+/// Formats tables can be read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// Sequence of row maps
+    Json,
+    /// Map of column keys to column values
+    JsonColumns,
+    /// Header with column keys followed by rows of values
+    Csv,
+}
+
+/// Formats tables can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned plain text
+    Table,
+    /// Sequence of row maps
+    Json,
+    /// Map of column keys to column values
+    JsonColumns,
+    Csv,
+    Markdown,
+}
+
+pub fn read_table(reader: impl io::Read, format: InputFormat) -> Result<Table, String> {
+    match format {
+        InputFormat::Json | InputFormat::JsonColumns => table_from_json(reader, format),
+        InputFormat::Csv => table_from_csv(reader),
+    }
+}
+
+fn value_to_json(value: &Value) -> Json {
+    serde_json::to_value(value).expect("Values are always representable as JSON")
+}
+
+/// Read a table with one of the serde formats of the crate: the row-wise sequence of maps of
+/// [`HashTable`], or the [`hashtable_columns_map`] map of column keys to column values.
+fn table_from_json(mut reader: impl io::Read, format: InputFormat) -> Result<Table, String> {
+    let mut source = String::new();
+    reader
+        .read_to_string(&mut source)
+        .map_err(|e| e.to_string())?;
+    let mut deserializer = serde_json::Deserializer::from_str(&source);
+    let table = if format == InputFormat::JsonColumns {
+        hashtable_columns_map::deserialize(&mut deserializer)
+    } else {
+        Table::deserialize(&mut deserializer)
+    };
+    table
+        .and_then(|table| deserializer.end().map(|()| table))
+        .map_err(|e| e.to_string())
+}
+
+/// Parse a CSV field, guessing its type.
+///
+/// Only finite numbers are parsed, so that fields like `NaN` or `inf` stay strings.
+fn value_from_field(field: &str) -> Value {
+    if field.is_empty() {
+        Value::Null
+    } else if let Ok(b) = field.parse() {
+        Value::Bool(b)
+    } else if let Ok(i) = field.parse() {
+        Value::Int(i)
+    } else if let Some(f) = field.parse::<f64>().ok().filter(|f| f.is_finite()) {
+        Value::Float(f)
+    } else {
+        Value::Str(field.to_owned())
+    }
+}
+
+fn value_to_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn table_from_csv(reader: impl io::Read) -> Result<Table, String> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let columns: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(str::to_owned)
+        .collect();
+    let mut table = Table::with_columns(columns.iter().cloned());
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let mut fields = record.iter();
+        table.push_row_with(|_| value_from_field(fields.next().unwrap_or_default()));
+    }
+    Ok(table)
+}
+
+pub fn write_table(
+    table: &Table,
+    format: OutputFormat,
+    mut writer: impl io::Write,
+) -> Result<(), String> {
+    let columns = table.column_keys_in_order();
+    let output = match format {
+        OutputFormat::Table => render_text(table, &columns, false),
+        OutputFormat::Markdown => render_text(table, &columns, true),
+        OutputFormat::Json => {
+            let rows: Vec<Json> = table
+                .iter()
+                .map(|row| {
+                    Json::Object(
+                        columns
+                            .iter()
+                            .map(|k| ((*k).clone(), value_to_json(row.get(*k).unwrap())))
+                            .collect(),
+                    )
+                })
+                .collect();
+            serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())? + "\n"
+        }
+        OutputFormat::JsonColumns => {
+            let object: Map<String, Json> = columns
+                .iter()
+                .map(|k| {
+                    let column = table.get_column(*k).unwrap();
+                    let values = column.iter().map(|v| value_to_json(v)).collect();
+                    ((*k).clone(), Json::Array(values))
+                })
+                .collect();
+            serde_json::to_string_pretty(&object).map_err(|e| e.to_string())? + "\n"
+        }
+        OutputFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(Vec::new());
+            let csv_error = |e: csv::Error| e.to_string();
+            csv_writer.write_record(&columns).map_err(csv_error)?;
+            for row in table {
+                let fields = columns
+                    .iter()
+                    .map(|k| csv_field(k, row.get(*k).unwrap()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                csv_writer.write_record(fields).map_err(csv_error)?;
+            }
+            String::from_utf8(csv_writer.into_inner().map_err(|e| e.to_string())?)
+                .map_err(|e| e.to_string())?
+        }
+    };
+    writer
+        .write_all(output.as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+/// CSV field of a value, failing if the field would be read back as a different value
+fn csv_field(column: &str, value: &Value) -> Result<String, String> {
+    let field = value_to_field(value);
+    if value_from_field(&field) == *value {
+        Ok(field)
+    } else {
+        Err(format!(
+            "the {} `{field}` in column `{column}` can't be read back from CSV, use JSON instead",
+            value.type_name()
+        ))
+    }
+}
+
+/// Escape the text of a markdown table cell
+fn escape_markdown(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+/// Render the table as aligned text, optionally as a markdown table
+fn render_text(table: &Table, columns: &[&String], markdown: bool) -> String {
+    let cell = |text: String| {
+        if markdown {
+            escape_markdown(&text)
+        } else {
+            text
+        }
+    };
+    let header: Vec<String> = columns.iter().map(|k| cell((*k).clone())).collect();
+    let cells: Vec<Vec<String>> = table
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|k| cell(value_to_field(row.get(*k).unwrap())))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, k)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([k.chars().count(), if markdown { 3 } else { 0 }])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut output = String::new();
+    let mut write_line = |cells: &mut dyn Iterator<Item = String>| {
+        let line = cells
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>();
+        if markdown {
+            let _ = writeln!(output, "| {} |", line.join(" | "));
+        } else {
+            let _ = writeln!(output, "{}", line.join("  ").trim_end());
+        }
+    };
+    write_line(&mut header.into_iter());
+    write_line(&mut widths.iter().map(|w| "-".repeat(*w)));
+    for row in cells {
+        write_line(&mut row.into_iter());
+    }
+    output
+}
@@ -0,0 +1,179 @@
+//! Command-line tool for inspecting and transforming serialized tables
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+use hash_table_datastruct::{expr::Expr, value::Value, HashTable};
+
+mod formats;
+#[cfg(test)]
+mod tests;
+
+use formats::{InputFormat, OutputFormat, Table};
+
+#[derive(Debug, Parser)]
+#[command(name = "hash-table", version, about)]
+struct Cli {
+    /// Input file, `-` for standard input
+    input: PathBuf,
+    /// Format of the input, detected from the file extension if not specified. `.json` files are
+    /// read as rows, use `--from json-columns` for a map of columns.
+    #[arg(long, value_enum)]
+    from: Option<InputFormat>,
+    /// Format of the output
+    #[arg(long, value_enum, global = true)]
+    to: Option<OutputFormat>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the whole table
+    Show,
+    /// Print the first rows of the table
+    Head {
+        /// Number of rows
+        #[arg(short = 'n', long, default_value_t = 10)]
+        rows: usize,
+    },
+    /// Keep only the listed columns, in the listed order
+    Select {
+        #[arg(required = true)]
+        columns: Vec<String>,
+    },
+    /// Keep only the rows matching an expression, e.g. `age > 30 && country == "NO"`
+    Filter { condition: String },
+    /// Sort the rows by the value of an expression
+    Sort {
+        key: String,
+        /// Sort in descending order
+        #[arg(long)]
+        desc: bool,
+    },
+    /// Convert the table to another format, set with `--to`
+    Convert,
+    /// Print statistics of each column
+    Stats,
+}
+
+fn input_format(cli: &Cli) -> Result<InputFormat, String> {
+    if let Some(format) = cli.from {
+        return Ok(format);
+    }
+    match cli.input.extension().and_then(|e| e.to_str()) {
+        Some("json") => Ok(InputFormat::Json),
+        Some("csv") => Ok(InputFormat::Csv),
+        _ => Err("can't detect the input format, specify it with `--from`".to_owned()),
+    }
+}
+
+fn read_input(path: &Path, format: InputFormat) -> Result<Table, String> {
+    let reader: Box<dyn Read> = if path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Box::new(BufReader::new(file))
+    };
+    formats::read_table(reader, format)
+}
+
+/// Table with statistics of every column of the `table`
+fn stats(table: &Table) -> Table {
+    let columns = ["column", "types", "count", "nulls", "min", "max", "mean"];
+    let mut result = HashTable::with_columns(columns.map(str::to_owned));
+    for key in table.column_keys_in_order() {
+        let column = table.get_column(key).unwrap();
+        let present: Vec<&Value> = column.iter().copied().filter(|v| !v.is_null()).collect();
+        let mut types: Vec<&str> = present.iter().map(|v| v.type_name()).collect();
+        types.sort_unstable();
+        types.dedup();
+        let numbers: Option<Vec<f64>> = present.iter().map(|v| v.as_f64()).collect();
+        let mean = match numbers {
+            Some(numbers) if !numbers.is_empty() => {
+                Value::Float(numbers.iter().sum::<f64>() / numbers.len() as f64)
+            }
+            _ => Value::Null,
+        };
+        let stats = [
+            Value::Str(key.clone()),
+            Value::Str(types.join("/")),
+            Value::from(present.len()),
+            Value::from(column.len() - present.len()),
+            present
+                .iter()
+                .copied()
+                .min_by(|a, b| a.total_cmp(b))
+                .cloned()
+                .into(),
+            present
+                .iter()
+                .copied()
+                .max_by(|a, b| a.total_cmp(b))
+                .cloned()
+                .into(),
+            mean,
+        ];
+        let mut stats = stats.into_iter();
+        result.push_row_with(|_| stats.next().unwrap());
+    }
+    result
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let mut table = read_input(&cli.input, input_format(&cli)?)?;
+    let table = match cli.command {
+        Command::Show => table,
+        Command::Head { rows } => {
+            let mut row = 0;
+            table.retain(|_| {
+                row += 1;
+                row <= rows
+            });
+            table
+        }
+        Command::Select { columns } => {
+            if let Some(missing) = columns.iter().find(|c| table.get_column(*c).is_none()) {
+                return Err(format!("unknown column `{missing}`"));
+            }
+            table.select(columns.iter().map(String::as_str))
+        }
+        Command::Filter { condition } => {
+            let condition = Expr::parse(&condition).map_err(|e| e.to_string())?;
+            table.filter_expr(&condition).map_err(|e| e.to_string())?
+        }
+        Command::Sort { key, desc } => {
+            let key = Expr::parse(&key).map_err(|e| e.to_string())?;
+            table.sort_by_expr(&key, desc).map_err(|e| e.to_string())?;
+            table
+        }
+        Command::Convert => {
+            if cli.to.is_none() {
+                return Err("`convert` requires the output format to be set with `--to`".to_owned());
+            }
+            table
+        }
+        Command::Stats => stats(&table),
+    };
+    let stdout = io::stdout().lock();
+    formats::write_table(
+        &table,
+        cli.to.unwrap_or(OutputFormat::Table),
+        BufWriter::new(stdout),
+    )
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use clap::Parser;
+use hash_table_datastruct::value::Value;
+
+use crate::{
+    formats::{read_table, write_table, InputFormat, OutputFormat, Table},
+    input_format, stats, Cli,
+};
+
+fn read(source: &str, format: InputFormat) -> Result<Table, String> {
+    read_table(source.as_bytes(), format)
+}
+
+fn write(table: &Table, format: OutputFormat) -> String {
+    let mut output = Vec::new();
+    write_table(table, format, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn sample() -> Table {
+    read(
+        r#"[{"name": "Ann", "age": 31, "score": 1.5}, {"age": 45, "name": "Bob", "score": null}]"#,
+        InputFormat::Json,
+    )
+    .unwrap()
+}
+
+#[test]
+fn json_rows_and_columns_are_read() {
+    let table = sample();
+    assert_eq!(table.column_keys_in_order(), ["name", "age", "score"]);
+    assert_eq!(table.get("age", 1), Some(&Value::Int(45)));
+    assert_eq!(table.get("score", 0), Some(&Value::Float(1.5)));
+    assert_eq!(table.get("score", 1), Some(&Value::Null));
+
+    let columns = read(
+        r#"{"name": ["Ann", "Bob"], "age": [31, 45], "score": [1.5, null]}"#,
+        InputFormat::JsonColumns,
+    )
+    .unwrap();
+    assert_eq!(
+        write(&columns, OutputFormat::Json),
+        write(&table, OutputFormat::Json)
+    );
+    assert_eq!(read("[]", InputFormat::Json).unwrap().rows_len(), 0);
+    assert!(read(r#"{"a": [1]}"#, InputFormat::Json).is_err());
+    assert!(read(r#"[{"a": 1}]"#, InputFormat::JsonColumns).is_err());
+}
+
+#[test]
+fn invalid_json_is_rejected() {
+    for source in [
+        r#"[{"a": 1}, {"b": 2}]"#,
+        r#"[{"a": 1}, {"a": 2, "b": 3}]"#,
+        r#"{"a": [1, 2], "b": [3]}"#,
+        r#"[{"a": 1}] trailing"#,
+        r#""not a table""#,
+    ] {
+        assert!(read(source, InputFormat::Json).is_err(), "{source}");
+    }
+}
+
+#[test]
+fn csv_fields_are_typed() {
+    let table = read("a,b,c,d\n1,2.5,true,x\n,-3,,\"y,z\"\n", InputFormat::Csv).unwrap();
+    assert_eq!(table.column_keys_in_order(), ["a", "b", "c", "d"]);
+    assert_eq!(
+        table.get_row(0).unwrap().to_vec(),
+        [
+            Value::Int(1),
+            Value::Float(2.5),
+            Value::Bool(true),
+            Value::from("x")
+        ]
+    );
+    assert_eq!(
+        table.get_row(1).unwrap().to_vec(),
+        [Value::Null, Value::Int(-3), Value::Null, Value::from("y,z")]
+    );
+}
+
+#[test]
+fn written_tables_are_read_back() {
+    let table = sample();
+    for (output, input) in [
+        (OutputFormat::Json, InputFormat::Json),
+        (OutputFormat::JsonColumns, InputFormat::JsonColumns),
+        (OutputFormat::Csv, InputFormat::Csv),
+    ] {
+        let read_back = read(&write(&table, output), input).unwrap();
+        assert_eq!(
+            write(&read_back, OutputFormat::Json),
+            write(&table, OutputFormat::Json)
+        );
+    }
+}
+
+#[test]
+fn csv_keeps_non_numeric_strings() {
+    let table = read("a\nNaN\ninf\n-infinity\n1e400\n", InputFormat::Csv).unwrap();
+    let strings = ["NaN", "inf", "-infinity", "1e400"].map(Value::from);
+    for (row, string) in strings.iter().enumerate() {
+        assert_eq!(table.get("a", row), Some(string));
+    }
+    let read_back = read(&write(&table, OutputFormat::Csv), InputFormat::Csv).unwrap();
+    assert_eq!(
+        write(&read_back, OutputFormat::Json),
+        write(&table, OutputFormat::Json)
+    );
+}
+
+#[test]
+fn csv_refuses_values_that_change_type() {
+    for value in [
+        Value::from(""),
+        Value::from("12"),
+        Value::from("true"),
+        Value::Float(f64::NAN),
+    ] {
+        let table = Table::from_column_keys_and_rows(["a".to_owned()], [[value]]);
+        let mut output = Vec::new();
+        assert!(write_table(&table, OutputFormat::Csv, &mut output).is_err());
+    }
+}
+
+#[test]
+fn text_formats_are_aligned() {
+    let table = read(r#"[{"k": "a|b", "value": 10}]"#, InputFormat::Json).unwrap();
+    assert_eq!(
+        write(&table, OutputFormat::Table),
+        "k    value\n---  -----\na|b  10\n"
+    );
+    assert_eq!(
+        write(&table, OutputFormat::Markdown),
+        "| k    | value |\n| ---- | ----- |\n| a\\|b | 10    |\n"
+    );
+
+    let table = read(r#"[{"a|b": "x\ny", "c": "d\\"}]"#, InputFormat::Json).unwrap();
+    assert_eq!(
+        write(&table, OutputFormat::Markdown),
+        "| a\\|b   | c   |\n| ------ | --- |\n| x<br>y | d\\\\ |\n"
+    );
+}
+
+#[test]
+fn input_format_is_detected_from_extension() {
+    let cli = Cli::try_parse_from(["hash-table", "data.csv", "show"]).unwrap();
+    assert_eq!(input_format(&cli), Ok(InputFormat::Csv));
+    let cli = Cli::try_parse_from(["hash-table", "data.json", "show"]).unwrap();
+    assert_eq!(input_format(&cli), Ok(InputFormat::Json));
+    let cli = Cli::try_parse_from(["hash-table", "-", "--from", "json-columns", "show"]).unwrap();
+    assert_eq!(input_format(&cli), Ok(InputFormat::JsonColumns));
+    let cli = Cli::try_parse_from(["hash-table", "data.txt", "show"]).unwrap();
+    assert!(input_format(&cli).is_err());
+}
+
+#[test]
+fn stats_summarize_columns() {
+    let stats = stats(&sample());
+    assert_eq!(stats.rows_len(), 3);
+    let score = stats.get_row(2).unwrap();
+    assert_eq!(score.get("column"), Some(&Value::from("score")));
+    assert_eq!(score.get("count"), Some(&Value::Int(1)));
+    assert_eq!(score.get("nulls"), Some(&Value::Int(1)));
+    assert_eq!(score.get("mean"), Some(&Value::Float(1.5)));
+}
@@ -0,0 +1,356 @@
+//! Compact self-describing binary format
+//!
+//! A table is written as:
+//!
+//! 1. the magic bytes `HTBL` and a one-byte format version,
+//! 2. the number of columns followed by the column keys in column-index order,
+//! 3. the number of rows,
+//! 4. all values in row-major order.
+//!
+//! Counts and lengths are little-endian `u64`. Keys and values are encoded through the
+//! [`BinaryValue`] trait. Unlike the serde representation, column keys are written only once.
+//!
+//! ## Example
+//! ```
+//! # use hash_table_datastruct::{HashTable, binary::BinaryRowReader};
+//! let table = HashTable::from_column_keys_and_rows(
+//!     ["x".to_owned(), "y".to_owned()],
+//!     [[1_i32, 2], [3, 4]],
+//! );
+//! let mut buffer = Vec::new();
+//! table.write_binary(&mut buffer).unwrap();
+//!
+//! let restored = HashTable::<String, i32>::read_binary(&mut buffer.as_slice()).unwrap();
+//! assert_eq!(restored.get("y", 1), Some(&4));
+//!
+//! let mut rows = BinaryRowReader::<_, String, i32>::new(buffer.as_slice()).unwrap();
+//! assert_eq!(rows.column_keys(), ["x", "y"]);
+//! assert_eq!(rows.next().unwrap().unwrap(), [1, 2]);
+//! ```
+
+use std::{
+    hash::Hash,
+    io::{self, BufWriter, Read, Write},
+    iter::FusedIterator,
+    marker::PhantomData,
+};
+
+use crate::{value::Value, HashTable};
+
+/// Magic bytes at the start of every table
+pub const MAGIC: [u8; 4] = *b"HTBL";
+
+/// Version of the format written by this crate
+pub const VERSION: u8 = 1;
+
+/// Values that can be written in the binary format
+pub trait BinaryValue: Sized {
+    /// Write the value to the `writer`
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Read a value written by [`Self::encode`] from the `reader`
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self>;
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn write_len<W: Write + ?Sized>(writer: &mut W, len: usize) -> io::Result<()> {
+    (len as u64).encode(writer)
+}
+
+fn read_len<R: Read + ?Sized>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(u64::decode(reader)?).map_err(|_| invalid_data("length does not fit in usize"))
+}
+
+macro_rules! impl_binary_number {
+    ($($t:ty),* $(,)?) => {$(
+        impl BinaryValue for $t {
+            fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+                let mut bytes = [0; std::mem::size_of::<$t>()];
+                reader.read_exact(&mut bytes)?;
+                Ok(<$t>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+impl_binary_number!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+/// Stored as a `u64` so that data is portable between platforms.
+impl BinaryValue for usize {
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        write_len(writer, *self)
+    }
+
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        read_len(reader)
+    }
+}
+
+/// Stored as an `i64` so that data is portable between platforms.
+impl BinaryValue for isize {
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as i64).encode(writer)
+    }
+
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        isize::try_from(i64::decode(reader)?)
+            .map_err(|_| invalid_data("value does not fit in isize"))
+    }
+}
+
+impl BinaryValue for bool {
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        u8::from(*self).encode(writer)
+    }
+
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        match u8::decode(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(invalid_data(format!("invalid bool byte {other}"))),
+        }
+    }
+}
+
+impl BinaryValue for char {
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        u32::from(*self).encode(writer)
+    }
+
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        let code = u32::decode(reader)?;
+        char::from_u32(code).ok_or_else(|| invalid_data(format!("invalid char {code:#x}")))
+    }
+}
+
+impl BinaryValue for String {
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        String::from_utf8(bytes).map_err(|e| invalid_data(e.to_string()))
+    }
+}
+
+/// A tag byte, `0` for `None` and `1` for `Some`, followed by the value if present.
+impl<T: BinaryValue> BinaryValue for Option<T> {
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            None => 0_u8.encode(writer),
+            Some(value) => {
+                1_u8.encode(writer)?;
+                value.encode(writer)
+            }
+        }
+    }
+
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        match u8::decode(reader)? {
+            0 => Ok(None),
+            1 => T::decode(reader).map(Some),
+            other => Err(invalid_data(format!("invalid option tag {other}"))),
+        }
+    }
+}
+
+/// A tag byte for the type of the value followed by its contents.
+impl BinaryValue for Value {
+    fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            Value::Null => 0_u8.encode(writer),
+            Value::Bool(b) => {
+                1_u8.encode(writer)?;
+                b.encode(writer)
+            }
+            Value::Int(i) => {
+                2_u8.encode(writer)?;
+                i.encode(writer)
+            }
+            Value::Float(f) => {
+                3_u8.encode(writer)?;
+                f.encode(writer)
+            }
+            Value::Str(s) => {
+                4_u8.encode(writer)?;
+                s.encode(writer)
+            }
+        }
+    }
+
+    fn decode<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        match u8::decode(reader)? {
+            0 => Ok(Value::Null),
+            1 => bool::decode(reader).map(Value::Bool),
+            2 => i64::decode(reader).map(Value::Int),
+            3 => f64::decode(reader).map(Value::Float),
+            4 => String::decode(reader).map(Value::Str),
+            other => Err(invalid_data(format!("invalid value tag {other}"))),
+        }
+    }
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: BinaryValue,
+    V: BinaryValue,
+{
+    /// Write the table in the binary format.
+    ///
+    /// Values are written through a buffer, so the `writer` doesn't need to be buffered.
+    pub fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        let writer = &mut BufWriter::new(writer);
+        writer.write_all(&MAGIC)?;
+        VERSION.encode(writer)?;
+        let keys = self.column_keys_in_order();
+        write_len(writer, keys.len())?;
+        for key in keys {
+            key.encode(writer)?;
+        }
+        write_len(writer, self.rows_len())?;
+        for value in &self.values_vector {
+            value.encode(writer)?;
+        }
+        writer.flush()
+    }
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: BinaryValue + Hash + Eq,
+    V: BinaryValue,
+{
+    /// Read a table written by [`Self::write_binary`].
+    ///
+    /// Values are read one at a time and nothing after the table is consumed, so a file should be
+    /// wrapped in a [`BufReader`](io::BufReader).
+    pub fn read_binary<R: Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        let BinaryRowReader {
+            reader,
+            columns,
+            remaining,
+            ..
+        } = BinaryRowReader::<_, K, V>::new(reader)?;
+        let mut table = HashTable::default();
+        let columns_len = columns.len();
+        for (i, key) in columns.into_iter().enumerate() {
+            if table.indices_table.insert(key, i).is_some() {
+                return Err(invalid_data(format!("duplicate column key at index {i}")));
+            }
+        }
+        for _ in 0..remaining {
+            for _ in 0..columns_len {
+                table.values_vector.push(V::decode(reader)?);
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Reader of tables in the binary format that yields one row at a time.
+///
+/// Rows are yielded as vectors of values in column-index order, matching [`Self::column_keys`].
+/// After an error the iterator stops.
+#[derive(Debug)]
+pub struct BinaryRowReader<R, K, V> {
+    reader: R,
+    columns: Vec<K>,
+    remaining: usize,
+    _values: PhantomData<fn() -> V>,
+}
+
+impl<R, K, V> BinaryRowReader<R, K, V>
+where
+    R: Read,
+    K: BinaryValue,
+    V: BinaryValue,
+{
+    /// Read the header of the table from the `reader`.
+    ///
+    /// Like [`HashTable::read_binary`], the reader should be buffered.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a hash table binary file"));
+        }
+        let version = u8::decode(&mut reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported format version {version}"
+            )));
+        }
+        let columns_len = read_len(&mut reader)?;
+        let columns = (0..columns_len)
+            .map(|_| K::decode(&mut reader))
+            .collect::<io::Result<_>>()?;
+        let remaining = read_len(&mut reader)?;
+        Ok(Self {
+            reader,
+            columns,
+            remaining: if columns_len == 0 { 0 } else { remaining },
+            _values: PhantomData,
+        })
+    }
+}
+
+impl<R, K, V> BinaryRowReader<R, K, V> {
+    /// Column keys in column-index order
+    pub fn column_keys(&self) -> &[K] {
+        &self.columns
+    }
+
+    /// Number of rows that haven't been read yet
+    pub fn remaining_rows(&self) -> usize {
+        self.remaining
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, K, V> Iterator for BinaryRowReader<R, K, V>
+where
+    R: Read,
+    V: BinaryValue,
+{
+    type Item = io::Result<Vec<V>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let row = (0..self.columns.len())
+            .map(|_| V::decode(&mut self.reader))
+            .collect::<io::Result<Vec<V>>>();
+        self.remaining = if row.is_ok() { self.remaining - 1 } else { 0 };
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+impl<R, K, V> FusedIterator for BinaryRowReader<R, K, V>
+where
+    R: Read,
+    V: BinaryValue,
+{
+}
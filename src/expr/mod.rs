@@ -0,0 +1,574 @@
+//! Expressions evaluated against table rows
+//!
+//! Expressions can be built in code with [`col`] and [`lit`] or parsed from a string, which
+//! allows describing row filters and derived columns in configuration files.
+//!
+//! ## Syntax
+//! - Literals: `42`, `1.5`, `"text"` or `'text'`, `true`, `false`, `null`
+//! - Columns: `col("name")` or a bare identifier `name`
+//! - Arithmetic: `+`, `-`, `*`, `/`, `%`, unary `-`
+//! - Comparison: `==`, `!=`, `<`, `<=`, `>`, `>=`
+//! - Logic: `&&`, `||`, `!`
+//! - Grouping with parentheses
+//!
+//! ## Example
+//! ```
+//! # use hash_table_datastruct::{HashTable, expr::Expr, value::Value};
+//! let people = HashTable::from_column_keys_and_rows(
+//!     ["age", "country"],
+//!     [
+//!         [Value::Int(25), Value::from("NO")],
+//!         [Value::Int(41), Value::from("NO")],
+//!         [Value::Int(52), Value::from("SE")],
+//!     ],
+//! );
+//! let filter: Expr = r#"col("age") > 30 && col("country") == "NO""#.parse().unwrap();
+//! let filtered = people.filter_expr(&filter).unwrap();
+//! assert_eq!(filtered.rows_len(), 1);
+//! assert_eq!(filtered.get("age", 0), Some(&Value::Int(41)));
+//! ```
+
+use std::{borrow::Borrow, cmp::Ordering, fmt, ops, str::FromStr};
+
+use crate::{
+    row::borrowed::HashTableRowBorrowed,
+    table::computed::ComputedHashTable,
+    typedefs::*,
+    value::{ToValue, Value},
+    HashTable,
+};
+
+pub(crate) mod parser;
+
+/// Expression tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    /// Value of the column with this key in the evaluated row
+    Column(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinaryOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Or => "||",
+            Self::And => "&&",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+        }
+    }
+}
+
+/// Error of parsing or evaluating an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprError {
+    /// The expression string is malformed
+    Parse { position: usize, message: String },
+    /// The row doesn't have the referenced column
+    UnknownColumn(String),
+    /// An operator was applied to values of unsupported types
+    Type {
+        op: &'static str,
+        left: &'static str,
+        right: Option<&'static str>,
+    },
+    /// Integer division or remainder by zero
+    DivisionByZero,
+    /// Integer arithmetic overflowed
+    Overflow,
+    /// Two result columns have the same key
+    DuplicateColumn(String),
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { position, message } => {
+                write!(f, "parse error at position {position}: {message}")
+            }
+            Self::UnknownColumn(column) => write!(f, "unknown column `{column}`"),
+            Self::Type {
+                op,
+                left,
+                right: None,
+            } => write!(f, "operator `{op}` can't be applied to {left}"),
+            Self::Type {
+                op,
+                left,
+                right: Some(right),
+            } => write!(f, "operator `{op}` can't be applied to {left} and {right}"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::Overflow => write!(f, "integer overflow"),
+            Self::DuplicateColumn(column) => write!(f, "duplicate result column `{column}`"),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// Expression that evaluates to the value of a column
+pub fn col(name: impl Into<String>) -> Expr {
+    Expr::Column(name.into())
+}
+
+/// Expression that evaluates to a constant
+pub fn lit(value: impl Into<Value>) -> Expr {
+    Expr::Literal(value.into())
+}
+
+impl Expr {
+    /// Parse an expression from a string.
+    pub fn parse(source: &str) -> Result<Self, ExprError> {
+        parser::parse(source)
+    }
+
+    fn binary(self, op: BinaryOp, other: impl Into<Expr>) -> Self {
+        Self::Binary(op, Box::new(self), Box::new(other.into()))
+    }
+
+    pub fn and(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::And, other)
+    }
+
+    pub fn or(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Or, other)
+    }
+
+    pub fn equals(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Eq, other)
+    }
+
+    pub fn not_equals(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Ne, other)
+    }
+
+    pub fn lt(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Lt, other)
+    }
+
+    pub fn le(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Le, other)
+    }
+
+    pub fn gt(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Gt, other)
+    }
+
+    pub fn ge(self, other: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Ge, other)
+    }
+
+    /// Keys of all columns referenced by the expression
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns<'e>(&'e self, columns: &mut Vec<&'e str>) {
+        match self {
+            Self::Literal(_) => {}
+            Self::Column(name) => {
+                if !columns.contains(&name.as_str()) {
+                    columns.push(name)
+                }
+            }
+            Self::Unary(_, expr) => expr.collect_columns(columns),
+            Self::Binary(_, left, right) => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+        }
+    }
+
+    /// Evaluate the expression, getting column values with `lookup`
+    pub fn eval_with<F>(&self, lookup: &F) -> Result<Value, ExprError>
+    where
+        F: Fn(&str) -> Option<Value>,
+    {
+        match self {
+            Self::Literal(value) => Ok(value.clone()),
+            Self::Column(name) => {
+                lookup(name).ok_or_else(|| ExprError::UnknownColumn(name.clone()))
+            }
+            Self::Unary(op, expr) => eval_unary(*op, expr.eval_with(lookup)?),
+            Self::Binary(BinaryOp::And, left, right) => {
+                if !truthy("&&", &left.eval_with(lookup)?)? {
+                    return Ok(Value::Bool(false));
+                }
+                Ok(Value::Bool(truthy("&&", &right.eval_with(lookup)?)?))
+            }
+            Self::Binary(BinaryOp::Or, left, right) => {
+                if truthy("||", &left.eval_with(lookup)?)? {
+                    return Ok(Value::Bool(true));
+                }
+                Ok(Value::Bool(truthy("||", &right.eval_with(lookup)?)?))
+            }
+            Self::Binary(op, left, right) => {
+                eval_binary(*op, left.eval_with(lookup)?, right.eval_with(lookup)?)
+            }
+        }
+    }
+
+    /// Evaluate the expression against a table row
+    pub fn eval<K, V>(&self, row: HashTableRowBorrowed<'_, K, V>) -> Result<Value, ExprError>
+    where
+        K: Borrow<str>,
+        K: Hash + Eq,
+        V: ToValue,
+    {
+        self.eval_with(&|column| row.get(column).map(ToValue::to_value))
+    }
+
+    /// Evaluate the expression as a condition.
+    ///
+    /// `null` is treated as `false`, values other than booleans are an error.
+    pub fn matches<K, V>(&self, row: HashTableRowBorrowed<'_, K, V>) -> Result<bool, ExprError>
+    where
+        K: Borrow<str>,
+        K: Hash + Eq,
+        V: ToValue,
+    {
+        truthy("condition", &self.eval(row)?)
+    }
+}
+
+/// Whether a value is true when used as a condition, null counts as false
+pub(crate) fn truthy(op: &'static str, value: &Value) -> Result<bool, ExprError> {
+    match value {
+        Value::Bool(b) => Ok(*b),
+        Value::Null => Ok(false),
+        other => Err(ExprError::Type {
+            op,
+            left: other.type_name(),
+            right: None,
+        }),
+    }
+}
+
+fn eval_unary(op: UnaryOp, value: Value) -> Result<Value, ExprError> {
+    match (op, value) {
+        (_, Value::Null) => Ok(Value::Null),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnaryOp::Neg, Value::Int(i)) => i.checked_neg().map(Value::Int).ok_or(ExprError::Overflow),
+        (UnaryOp::Neg, Value::Float(f)) => Ok(Value::Float(-f)),
+        (op, other) => Err(ExprError::Type {
+            op: match op {
+                UnaryOp::Not => "!",
+                UnaryOp::Neg => "-",
+            },
+            left: other.type_name(),
+            right: None,
+        }),
+    }
+}
+
+fn eval_binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, ExprError> {
+    let type_error = |left: &Value, right: &Value| ExprError::Type {
+        op: op.symbol(),
+        left: left.type_name(),
+        right: Some(right.type_name()),
+    };
+    match op {
+        BinaryOp::Eq => return Ok(Value::Bool(left.compare(&right) == Some(Ordering::Equal))),
+        BinaryOp::Ne => return Ok(Value::Bool(left.compare(&right) != Some(Ordering::Equal))),
+        _ => {}
+    }
+    if left.is_null() || right.is_null() {
+        return Ok(Value::Null);
+    }
+    let ordering = |expected: fn(Ordering) -> bool| {
+        left.compare(&right)
+            .map(|o| Value::Bool(expected(o)))
+            .ok_or_else(|| type_error(&left, &right))
+    };
+    match op {
+        BinaryOp::Lt => ordering(Ordering::is_lt),
+        BinaryOp::Le => ordering(Ordering::is_le),
+        BinaryOp::Gt => ordering(Ordering::is_gt),
+        BinaryOp::Ge => ordering(Ordering::is_ge),
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+            match (op, &left, &right) {
+                (BinaryOp::Add, Value::Str(a), Value::Str(b)) => {
+                    return Ok(Value::Str(format!("{a}{b}")))
+                }
+                (BinaryOp::Div | BinaryOp::Rem, Value::Int(_), Value::Int(0)) => {
+                    return Err(ExprError::DivisionByZero)
+                }
+                _ => {}
+            }
+            let (int_op, float_op): (IntOp, FloatOp) = match op {
+                BinaryOp::Add => (i64::checked_add, ops::Add::add),
+                BinaryOp::Sub => (i64::checked_sub, ops::Sub::sub),
+                BinaryOp::Mul => (i64::checked_mul, ops::Mul::mul),
+                BinaryOp::Div => (i64::checked_div, ops::Div::div),
+                _ => (i64::checked_rem, ops::Rem::rem),
+            };
+            arithmetic(&left, &right, int_op, float_op)
+                .unwrap_or_else(|| Err(type_error(&left, &right)))
+        }
+        BinaryOp::And | BinaryOp::Or | BinaryOp::Eq | BinaryOp::Ne => {
+            unreachable!("Handled before")
+        }
+    }
+}
+
+type IntOp = fn(i64, i64) -> Option<i64>;
+type FloatOp = fn(f64, f64) -> f64;
+
+/// Apply a numeric operator. Integers stay integers, any float operand makes the result a float.
+///
+/// Returns None if the operands are not numbers.
+fn arithmetic(
+    left: &Value,
+    right: &Value,
+    int_op: IntOp,
+    float_op: FloatOp,
+) -> Option<Result<Value, ExprError>> {
+    match (left, right) {
+        (Value::Int(a), Value::Int(b)) => {
+            Some(int_op(*a, *b).map(Value::Int).ok_or(ExprError::Overflow))
+        }
+        _ => Some(Ok(Value::Float(float_op(left.as_f64()?, right.as_f64()?)))),
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Expr {
+    /// Formats the expression in the syntax accepted by [`Expr::parse`]
+    ///
+    /// Parsing the output gives back the same expression, except for infinite and NaN float
+    /// literals, which have no syntax.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(Value::Str(s)) => write_string(f, s),
+            // The float formatting of `Display` never uses an exponent, which the parser doesn't
+            // support
+            Self::Literal(Value::Float(x)) if x.is_finite() && x.fract() == 0.0 => {
+                write!(f, "{x}.0")
+            }
+            Self::Literal(Value::Float(x)) if x.is_finite() => write!(f, "{x}"),
+            Self::Literal(value) => write!(f, "{value}"),
+            Self::Column(name) => {
+                f.write_str("col(")?;
+                write_string(f, name)?;
+                f.write_str(")")
+            }
+            Self::Unary(UnaryOp::Not, expr) => write!(f, "!({expr})"),
+            Self::Unary(UnaryOp::Neg, expr) => write!(f, "-({expr})"),
+            Self::Binary(op, left, right) => write!(f, "({left} {} {right})", op.symbol()),
+        }
+    }
+}
+
+/// Write a string literal with only the escapes the parser understands
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            // Other characters, including control characters, are read verbatim
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl<T: Into<Value>> From<T> for Expr {
+    fn from(value: T) -> Self {
+        lit(value)
+    }
+}
+
+impl ops::Not for Expr {
+    type Output = Expr;
+
+    fn not(self) -> Self::Output {
+        Expr::Unary(UnaryOp::Not, Box::new(self))
+    }
+}
+
+impl ops::Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Self::Output {
+        Expr::Unary(UnaryOp::Neg, Box::new(self))
+    }
+}
+
+macro_rules! impl_expr_op {
+    ($($trait:ident $method:ident $op:ident),*) => {$(
+        impl<T: Into<Expr>> ops::$trait<T> for Expr {
+            type Output = Expr;
+
+            fn $method(self, rhs: T) -> Self::Output {
+                self.binary(BinaryOp::$op, rhs)
+            }
+        }
+    )*};
+}
+
+impl_expr_op!(Add add Add, Sub sub Sub, Mul mul Mul, Div div Div, Rem rem Rem);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Expr {
+    /// Serializes the expression as a string
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Expr {
+    /// Parses the expression from a string
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let source = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Expr::parse(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: Borrow<str>,
+    K: Hash + Eq,
+    V: ToValue,
+{
+    /// Copy the rows for which the `condition` expression evaluates to `true`.
+    pub fn filter_expr(&self, condition: &Expr) -> Result<Self, ExprError>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut error = None;
+        let result = self.filter(|row| {
+            error.is_none()
+                && condition
+                    .matches(row)
+                    .map_err(|e| error = Some(e))
+                    .unwrap_or(false)
+        });
+        error.map_or(Ok(result), Err)
+    }
+
+    /// Build a table of [`Value`]s with a column for each of the `(key, expression)` pairs.
+    ///
+    /// Fails with [`ExprError::DuplicateColumn`] if a key appears more than once.
+    pub fn select_expr<I>(&self, columns: I) -> Result<HashTable<K, Value>, ExprError>
+    where
+        I: IntoIterator<Item = (K, Expr)>,
+    {
+        let (keys, exprs): (Vec<K>, Vec<Expr>) = columns.into_iter().unzip();
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].contains(key) {
+                return Err(ExprError::DuplicateColumn(key.borrow().to_owned()));
+            }
+        }
+        let mut result = HashTable::with_columns_and_capacity(keys, self.rows_len());
+        for row in self {
+            for expr in &exprs {
+                result.values_vector.push(expr.eval(row)?);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Sort the rows by the value of the `key` expression, using [`Value::total_cmp`].
+    ///
+    /// The sort is stable.
+    pub fn sort_by_expr(&mut self, key: &Expr, descending: bool) -> Result<(), ExprError> {
+        let keys = self
+            .iter()
+            .map(|row| key.eval(row))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_by(|a, b| {
+            let ordering = keys[*a].total_cmp(&keys[*b]);
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        self.reorder_rows(&order);
+        Ok(())
+    }
+
+    /// Add a column with values of the expression evaluated for every row.
+    pub fn insert_column_expr(&mut self, column: K, expr: &Expr) -> Result<(), ExprError>
+    where
+        V: From<Value>,
+    {
+        let values = self
+            .iter()
+            .map(|row| expr.eval(row).map(V::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.insert_column(column, values);
+        Ok(())
+    }
+}
+
+impl<K, V> ComputedHashTable<K, V>
+where
+    K: Borrow<str>,
+    K: Hash + Eq,
+    V: ToValue + From<Value>,
+{
+    /// Register a cached computed column with values of the expression.
+    ///
+    /// Rows for which the expression fails to evaluate get [`Value::Null`].
+    pub fn add_expr_column(&mut self, column: K, expr: Expr) {
+        self.add_computed_column(column, move |row| {
+            V::from(expr.eval(row).unwrap_or(Value::Null))
+        })
+    }
+}
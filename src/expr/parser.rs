@@ -0,0 +1,437 @@
+//! Parser of the expression syntax
+
+use super::{col, BinaryOp, Expr, ExprError, UnaryOp};
+use crate::value::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Magnitude of an integer, which is negated by a leading `-`
+    Int(u64),
+    Float(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn error(position: usize, message: impl Into<String>) -> ExprError {
+    ExprError::Parse {
+        position,
+        message: message.into(),
+    }
+}
+
+/// Operators, longest first so that `<=` is not read as `<`
+///
+/// `=` and `<>` are only accepted by the SQL dialect.
+const OPERATORS: [&str; 16] = [
+    "&&", "||", "==", "!=", "<>", "<=", ">=", "<", ">", "=", "!", "+", "-", "*", "/", "%",
+];
+
+/// Words that end an expression in the SQL dialect and can't be used as bare column names
+const SQL_KEYWORDS: [&str; 17] = [
+    "select", "from", "join", "inner", "left", "on", "where", "group", "order", "by", "limit",
+    "offset", "as", "asc", "desc", "and", "or",
+];
+
+/// Split the source into tokens.
+///
+/// In the SQL dialect identifiers can be qualified with dots, as in `table.column`.
+fn tokenize(source: &str, sql: bool) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(position, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' || c == ',' {
+            chars.next();
+            let token = match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                _ => Token::Comma,
+            };
+            tokens.push((position, token));
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    None => return Err(error(position, "unterminated string")),
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, escaped)) => value.push(escaped),
+                        None => return Err(error(position, "unterminated string")),
+                    },
+                    Some((_, end)) if end == c => break,
+                    Some((_, other)) => value.push(other),
+                }
+            }
+            tokens.push((position, Token::Str(value)));
+        } else if c.is_ascii_digit() {
+            let mut end = position;
+            let mut is_float = false;
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_ascii_digit() || c == '_' || (c == '.' && !is_float) {
+                    is_float |= c == '.';
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let text = source[position..end].replace('_', "");
+            let token = if is_float {
+                text.parse().map(Token::Float).ok()
+            } else {
+                text.parse().map(Token::Int).ok()
+            };
+            tokens.push((
+                position,
+                token.ok_or_else(|| error(position, format!("invalid number `{text}`")))?,
+            ));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = position;
+            while let Some(&(i, c)) = chars.peek() {
+                let qualifies = || {
+                    sql && c == '.'
+                        && source[i + 1..]
+                            .chars()
+                            .next()
+                            .is_some_and(|c| c.is_alphabetic() || c == '_')
+                };
+                if c.is_alphanumeric() || c == '_' || qualifies() {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push((position, Token::Ident(source[position..end].to_owned())));
+        } else {
+            let op = OPERATORS
+                .into_iter()
+                .find(|op| source[position..].starts_with(op))
+                .ok_or_else(|| error(position, format!("unexpected character `{c}`")))?;
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push((position, Token::Op(op)));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser of expressions.
+///
+/// In the SQL dialect `AND`, `OR` and `NOT` keywords, `=` and `<>` are accepted as operators and
+/// expressions end at clause keywords, which allows the query parser to share this parser.
+pub(crate) struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    source_len: usize,
+    sql: bool,
+}
+
+impl Parser {
+    pub(crate) fn new(source: &str, sql: bool) -> Result<Self, ExprError> {
+        Ok(Self {
+            tokens: tokenize(source, sql)?,
+            position: 0,
+            source_len: source.len(),
+            sql,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, t)| t)
+    }
+
+    /// Whether all of the input was consumed
+    pub(crate) fn at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// Byte offset of the next token in the source
+    pub(crate) fn offset(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.source_len, |(offset, _)| *offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).map(|(_, t)| t.clone());
+        self.position += 1;
+        token
+    }
+
+    /// The next token as an operator, with SQL operators translated to their regular form
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.peek()? {
+            Token::Op("=") if self.sql => Some("=="),
+            Token::Op("<>") if self.sql => Some("!="),
+            Token::Op(op) => Some(op),
+            Token::Ident(ident) if self.sql => match ident.to_lowercase().as_str() {
+                "and" => Some("&&"),
+                "or" => Some("||"),
+                "not" => Some("!"),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub(crate) fn eat_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        let op = self.peek_op().filter(|op| ops.contains(op))?;
+        self.position += 1;
+        Some(op)
+    }
+
+    /// Parse a full expression
+    pub(crate) fn expr(&mut self) -> Result<Expr, ExprError> {
+        self.or()
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<(), ExprError> {
+        let offset = self.offset();
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(error(offset, format!("expected {description}"))),
+        }
+    }
+
+    /// Parse a left-associative chain of binary operators
+    fn binary_chain(
+        &mut self,
+        ops: &[&'static str],
+        operand: fn(&mut Self) -> Result<Expr, ExprError>,
+    ) -> Result<Expr, ExprError> {
+        let mut left = operand(self)?;
+        while let Some(op) = self.eat_op(ops) {
+            let right = operand(self)?;
+            left = Expr::Binary(binary_op(op), Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expr, ExprError> {
+        self.binary_chain(&["||"], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, ExprError> {
+        self.binary_chain(&["&&"], Self::negation)
+    }
+
+    /// `NOT` keyword of the SQL dialect, which has lower precedence than comparisons
+    fn negation(&mut self) -> Result<Expr, ExprError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) if self.sql && ident.eq_ignore_ascii_case("not") => {
+                self.position += 1;
+                Ok(Expr::Unary(UnaryOp::Not, Box::new(self.negation()?)))
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ExprError> {
+        let left = self.sum()?;
+        match self.eat_op(&["==", "!=", "<", "<=", ">", ">="]) {
+            Some(op) => Ok(Expr::Binary(
+                binary_op(op),
+                Box::new(left),
+                Box::new(self.sum()?),
+            )),
+            None => Ok(left),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, ExprError> {
+        self.binary_chain(&["+", "-"], Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, ExprError> {
+        self.binary_chain(&["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExprError> {
+        match self.eat_op(&["!", "-"]) {
+            Some("!") => Ok(Expr::Unary(UnaryOp::Not, Box::new(self.unary()?))),
+            // A minus sign directly before a number is part of the literal, so that `i64::MIN` can
+            // be written and negative literals are read back as they are displayed.
+            Some(_) => match self.peek() {
+                Some(&Token::Int(i)) => {
+                    let offset = self.offset();
+                    self.next();
+                    0i64.checked_sub_unsigned(i)
+                        .map(|i| Expr::Literal(Value::Int(i)))
+                        .ok_or_else(|| error(offset, format!("integer `-{i}` is out of range")))
+                }
+                Some(&Token::Float(f)) => {
+                    self.next();
+                    Ok(Expr::Literal(Value::Float(-f)))
+                }
+                _ => Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.unary()?))),
+            },
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ExprError> {
+        let offset = self.offset();
+        match self.next() {
+            Some(Token::Int(i)) => i64::try_from(i)
+                .map(|i| Expr::Literal(Value::Int(i)))
+                .map_err(|_| error(offset, format!("integer `{i}` is out of range"))),
+            Some(Token::Float(f)) => Ok(Expr::Literal(Value::Float(f))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::Str(s))),
+            Some(Token::LParen) => {
+                let expr = self.or()?;
+                self.expect(Token::RParen, "`)`")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident))
+                if self.sql && SQL_KEYWORDS.contains(&ident.to_lowercase().as_str()) =>
+            {
+                Err(error(offset, format!("unexpected keyword `{ident}`")))
+            }
+            Some(Token::Ident(ident)) => match if self.sql {
+                ident.to_lowercase()
+            } else {
+                ident.clone()
+            }
+            .as_str()
+            {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                "col" if self.peek() == Some(&Token::LParen) => {
+                    self.next();
+                    let offset = self.offset();
+                    let Some(Token::Str(name)) = self.next() else {
+                        return Err(error(offset, "expected a column name string"));
+                    };
+                    self.expect(Token::RParen, "`)`")?;
+                    Ok(col(name))
+                }
+                _ => Ok(col(ident)),
+            },
+            Some(token) => Err(error(offset, format!("unexpected token {token:?}"))),
+            None => Err(error(offset, "unexpected end of expression")),
+        }
+    }
+}
+
+/// Helpers used by the query parser
+#[cfg(feature = "query")]
+impl Parser {
+    /// Consume the next token if it's the (case-insensitive) `keyword`
+    pub(crate) fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn expect_keyword(&mut self, keyword: &str) -> Result<(), ExprError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(error(self.offset(), format!("expected `{keyword}`")))
+        }
+    }
+
+    pub(crate) fn eat_comma(&mut self) -> bool {
+        self.eat_token(Token::Comma)
+    }
+
+    pub(crate) fn expect_rparen(&mut self) -> Result<(), ExprError> {
+        self.expect(Token::RParen, "`)`")
+    }
+
+    fn eat_token(&mut self, token: Token) -> bool {
+        if self.peek() == Some(&token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume an identifier or a string, used for names of tables and columns
+    pub(crate) fn name(&mut self) -> Result<String, ExprError> {
+        let offset = self.offset();
+        match self.next() {
+            Some(Token::Ident(name) | Token::Str(name)) => Ok(name),
+            _ => Err(error(offset, "expected a name")),
+        }
+    }
+
+    /// Consume an identifier that is not a keyword, used for optional aliases
+    pub(crate) fn eat_bare_name(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(name)) if !SQL_KEYWORDS.contains(&name.to_lowercase().as_str()) => {
+                let name = name.clone();
+                self.position += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Consume a call of one of the functions in `names` up to the opening parenthesis
+    pub(crate) fn eat_function(&mut self, names: &[&'static str]) -> Option<&'static str> {
+        let Some((Token::Ident(name), Some((_, Token::LParen)))) =
+            self.peek().map(|t| (t, self.tokens.get(self.position + 1)))
+        else {
+            return None;
+        };
+        let name = names
+            .iter()
+            .find(|candidate| name.eq_ignore_ascii_case(candidate))?;
+        self.position += 2;
+        Some(name)
+    }
+
+    pub(crate) fn integer(&mut self) -> Result<usize, ExprError> {
+        let offset = self.offset();
+        match self.next() {
+            Some(Token::Int(i)) => {
+                usize::try_from(i).map_err(|_| error(offset, "expected a positive integer"))
+            }
+            _ => Err(error(offset, "expected an integer")),
+        }
+    }
+}
+
+fn binary_op(op: &str) -> BinaryOp {
+    match op {
+        "||" => BinaryOp::Or,
+        "&&" => BinaryOp::And,
+        "==" => BinaryOp::Eq,
+        "!=" => BinaryOp::Ne,
+        "<" => BinaryOp::Lt,
+        "<=" => BinaryOp::Le,
+        ">" => BinaryOp::Gt,
+        ">=" => BinaryOp::Ge,
+        "+" => BinaryOp::Add,
+        "-" => BinaryOp::Sub,
+        "*" => BinaryOp::Mul,
+        "/" => BinaryOp::Div,
+        "%" => BinaryOp::Rem,
+        _ => unreachable!("Only known operators are tokenized"),
+    }
+}
+
+pub(super) fn parse(source: &str) -> Result<Expr, ExprError> {
+    let mut parser = Parser::new(source, false)?;
+    let expr = parser.expr()?;
+    if !parser.at_end() {
+        return Err(error(parser.offset(), "unexpected trailing input"));
+    }
+    Ok(expr)
+}
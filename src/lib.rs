@@ -7,12 +7,26 @@
 ))]
 compile_error!("Due to how rust features work, you need to enable the `hashbrown-serde` feature to use both hashbrown and serde");
 
+// Lets code generated by `#[derive(TableRow)]` refer to this crate from inside it
+extern crate self as hash_table_datastruct;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod binary;
 pub mod column;
+pub mod expr;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "query")]
+pub mod query;
+pub mod record;
 pub mod row;
+pub mod stats;
 pub mod table;
 #[cfg(test)]
 mod tests;
 pub mod typedefs;
+pub mod value;
 pub use table::HashTable;
 #[doc(hidden)]
 pub use typedefs::*;
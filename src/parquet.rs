@@ -0,0 +1,133 @@
+//! Reading and writing tables as Parquet files
+//!
+//! Tables are converted through Arrow record batches, so values must implement
+//! [`ArrowValue`](crate::arrow::ArrowValue). Columns are written in column-index order.
+//!
+//! ## Example
+//! ```
+//! # use hash_table_datastruct::{HashTable, parquet::ParquetWriteOptions};
+//! let table = HashTable::from_column_keys_and_rows(
+//!     ["id".to_owned(), "score".to_owned()],
+//!     [[1_i64, 90], [2, 75], [3, 82]],
+//! );
+//! let path = std::env::temp_dir().join("hash_table_parquet_example.parquet");
+//! table
+//!     .write_parquet(&path, &ParquetWriteOptions::default().with_row_group_size(2))
+//!     .unwrap();
+//! let restored = HashTable::<String, i64>::read_parquet(&path).unwrap();
+//! assert_eq!(restored.get("score", 2), Some(&82));
+//! # std::fs::remove_file(path).unwrap();
+//! ```
+
+use std::{fs::File, io::Write, path::Path};
+
+use ::parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter},
+    errors::ParquetError,
+    file::{properties::WriterProperties, reader::ChunkReader},
+};
+use arrow_array::RecordBatchReader;
+
+pub use ::parquet::basic::{Compression, ZstdLevel};
+
+use crate::{arrow::ArrowValue, HashTable};
+
+/// Options of writing a Parquet file
+#[derive(Debug, Clone)]
+pub struct ParquetWriteOptions {
+    /// Maximum number of rows in a row group
+    pub row_group_size: usize,
+    /// Compression codec of the column chunks
+    pub compression: Compression,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            row_group_size: 1024 * 1024,
+            compression: Compression::SNAPPY,
+        }
+    }
+}
+
+impl ParquetWriteOptions {
+    pub fn with_row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = row_group_size;
+        self
+    }
+
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    fn writer_properties(&self) -> WriterProperties {
+        WriterProperties::builder()
+            .set_max_row_group_size(self.row_group_size)
+            .set_compression(self.compression)
+            .build()
+    }
+}
+
+impl<V: ArrowValue> HashTable<String, V> {
+    /// Read a table from a Parquet file.
+    pub fn read_parquet(path: impl AsRef<Path>) -> Result<Self, ParquetError> {
+        Self::read_parquet_from(File::open(path)?)
+    }
+
+    /// Read a table from Parquet data, with columns in the order of the file schema.
+    ///
+    /// Fails if the schema has several fields with the same name.
+    pub fn read_parquet_from<R>(reader: R) -> Result<Self, ParquetError>
+    where
+        R: ChunkReader + 'static,
+    {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(reader)?.build()?;
+        let schema = reader.schema();
+        let mut columns: Vec<(String, Vec<V>)> = Vec::with_capacity(schema.fields().len());
+        for field in schema.fields() {
+            if columns.iter().any(|(key, _)| key == field.name()) {
+                return Err(ParquetError::General(format!(
+                    "duplicate column `{}`",
+                    field.name()
+                )));
+            }
+            columns.push((field.name().clone(), Vec::new()));
+        }
+        for batch in reader {
+            let batch = batch?;
+            for ((_, values), array) in columns.iter_mut().zip(batch.columns()) {
+                values.extend(V::from_array(array.as_ref())?);
+            }
+        }
+        let mut table = Self::default();
+        table.insert_columns(columns);
+        Ok(table)
+    }
+
+    /// Write the table to a Parquet file, replacing it if it exists.
+    pub fn write_parquet(
+        &self,
+        path: impl AsRef<Path>,
+        options: &ParquetWriteOptions,
+    ) -> Result<(), ParquetError> {
+        self.write_parquet_to(File::create(path)?, options)
+    }
+
+    /// Write the table in the Parquet format.
+    pub fn write_parquet_to<W>(
+        &self,
+        writer: W,
+        options: &ParquetWriteOptions,
+    ) -> Result<(), ParquetError>
+    where
+        W: Write + Send,
+    {
+        let batch = self.to_record_batch()?;
+        let mut writer =
+            ArrowWriter::try_new(writer, batch.schema(), Some(options.writer_properties()))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
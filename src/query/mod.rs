@@ -0,0 +1,616 @@
+//! SQL-like queries over tables of [`Value`]s
+//!
+//! Supports a subset of `SELECT`:
+//! ```text
+//! SELECT * | item [AS name], ...
+//! FROM table [[AS] alias]
+//! [[INNER | LEFT [OUTER]] JOIN table [[AS] alias] ON condition] ...
+//! [WHERE condition]
+//! [GROUP BY expr, ...]
+//! [ORDER BY expr [ASC | DESC], ...]
+//! [LIMIT n [OFFSET m]]
+//! ```
+//! Expressions use the syntax of [`crate::expr`], with `AND`, `OR`, `NOT`, `=` and `<>` accepted
+//! as well. Select items can be the aggregate functions `count(*)`, `count(expr)`, `sum`, `avg`,
+//! `min` and `max`, which can't be nested inside other expressions.
+//!
+//! Joined rows have the columns of every table. A column can be referenced as `table.column`,
+//! using the alias of the table if it has one, or by its name alone if only one of the tables has
+//! a column with this name. `SELECT *` names the result columns the same way. A join compares
+//! every row with every row of the joined table, and a `LEFT JOIN` keeps the rows without a match
+//! with null values in the columns of the joined table.
+//!
+//! In grouped queries, select items that are not aggregates are evaluated against the first row
+//! of each group, so they should only reference the grouping columns.
+//!
+//! ## Example
+//! ```
+//! # use hash_table_datastruct::{HashTable, query::Catalog, value::Value};
+//! let sales = HashTable::from_column_keys_and_rows(
+//!     ["region".to_owned(), "amount".to_owned()],
+//!     [
+//!         [Value::from("north"), Value::Int(10)],
+//!         [Value::from("south"), Value::Int(5)],
+//!         [Value::from("north"), Value::Int(7)],
+//!     ],
+//! );
+//! let regions = HashTable::from_column_keys_and_rows(
+//!     ["region".to_owned(), "manager".to_owned()],
+//!     [[Value::from("north"), Value::from("Ada")]],
+//! );
+//! let mut catalog = Catalog::new();
+//! catalog.add("sales", &sales);
+//! let result = catalog
+//!     .query("SELECT region, sum(amount) AS total FROM sales GROUP BY region ORDER BY total DESC")
+//!     .unwrap();
+//! assert_eq!(result.get("region", 0), Some(&Value::from("north")));
+//! assert_eq!(result.get("total", 0), Some(&Value::Int(17)));
+//!
+//! catalog.add("regions", &regions);
+//! let result = catalog
+//!     .query("SELECT s.region, manager FROM sales s LEFT JOIN regions r ON s.region = r.region")
+//!     .unwrap();
+//! assert_eq!(result.get("s.region", 1), Some(&Value::from("south")));
+//! assert_eq!(result.get("manager", 1), Some(&Value::Null));
+//! ```
+
+use std::{borrow::Cow, cmp::Ordering, fmt, str::FromStr};
+
+use crate::{
+    expr::{truthy, Expr, ExprError},
+    typedefs::HashMap,
+    value::Value,
+    HashTable,
+};
+
+mod parser;
+
+/// Error of parsing or executing a query
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryError {
+    /// The query string is malformed or an expression failed to evaluate
+    Expr(ExprError),
+    /// A table in the `FROM` or a `JOIN` clause is not in the catalog
+    UnknownTable(String),
+    /// Two joined tables have the same name or alias
+    DuplicateTable(String),
+    /// A column name without a table is in several joined tables
+    AmbiguousColumn(String),
+    /// Two result columns have the same name
+    DuplicateColumn(String),
+    /// An aggregate function was applied to values it can't aggregate
+    Aggregate {
+        function: Aggregate,
+        value_type: &'static str,
+    },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expr(e) => write!(f, "{e}"),
+            Self::UnknownTable(table) => write!(f, "unknown table `{table}`"),
+            Self::DuplicateTable(table) => write!(f, "table `{table}` is joined more than once"),
+            Self::AmbiguousColumn(column) => {
+                write!(
+                    f,
+                    "column `{column}` is in several tables, qualify it with a table"
+                )
+            }
+            Self::DuplicateColumn(column) => write!(f, "duplicate result column `{column}`"),
+            Self::Aggregate {
+                function,
+                value_type,
+            } => write!(
+                f,
+                "`{function}` can't aggregate values of type {value_type}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl From<ExprError> for QueryError {
+    fn from(value: ExprError) -> Self {
+        Self::Expr(value)
+    }
+}
+
+/// Aggregate function of a select item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        })
+    }
+}
+
+/// Table of the `FROM` or a `JOIN` clause
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRef {
+    /// Name of the table in the catalog
+    pub table: String,
+    pub alias: Option<String>,
+}
+
+impl TableRef {
+    /// Name that qualifies the columns of the table, the alias if there is one
+    pub fn name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.table)
+    }
+}
+
+/// Kind of a `JOIN` clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Only rows that have a match in the joined table
+    Inner,
+    /// Rows without a match are kept with null values in the columns of the joined table
+    Left,
+}
+
+/// `JOIN` clause
+#[derive(Debug, Clone, PartialEq)]
+pub struct Join {
+    pub kind: JoinKind,
+    pub table: TableRef,
+    pub on: Expr,
+}
+
+/// Item of the `SELECT` list
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectItem {
+    /// `*`, all columns of the joined tables in table and column-index order
+    Wildcard,
+    Expr {
+        expr: Expr,
+        alias: Option<String>,
+    },
+    /// Aggregate function, `None` argument stands for `count(*)`
+    Aggregate {
+        function: Aggregate,
+        argument: Option<Expr>,
+        alias: Option<String>,
+    },
+}
+
+/// Parsed `SELECT` query
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub select: Vec<SelectItem>,
+    pub from: TableRef,
+    pub joins: Vec<Join>,
+    pub filter: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    /// Sort keys, `true` for descending order
+    pub order_by: Vec<(Expr, bool)>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Named tables that queries can select from
+#[derive(Debug, Clone, Default)]
+pub struct Catalog<'t> {
+    tables: HashMap<String, &'t HashTable<String, Value>>,
+}
+
+impl<'t> Catalog<'t> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the `table` available to queries under the `name`, replacing the previous table with
+    /// this name.
+    pub fn add(&mut self, name: impl Into<String>, table: &'t HashTable<String, Value>) {
+        self.tables.insert(name.into(), table);
+    }
+
+    /// Parse and execute a query
+    pub fn query(&self, query: &str) -> Result<HashTable<String, Value>, QueryError> {
+        Query::parse(query)?.execute(self)
+    }
+}
+
+/// Columns of the joined tables, in the order of the values of the joined rows
+#[derive(Default)]
+struct SourceColumns {
+    /// Name of the table and key of every column
+    columns: Vec<(String, String)>,
+}
+
+impl SourceColumns {
+    /// Add the columns of a joined table
+    fn add(&mut self, name: &str, table: &HashTable<String, Value>) {
+        self.columns.extend(
+            table
+                .column_keys_in_order()
+                .into_iter()
+                .map(|column| (name.to_owned(), column.clone())),
+        );
+    }
+
+    /// Position of a column given by its key alone or as `table.column`.
+    ///
+    /// Returns None for a key without a table that is in several tables.
+    fn index(&self, name: &str) -> Option<usize> {
+        let mut matching = self
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, column))| column == name);
+        match (matching.next(), matching.next()) {
+            (Some((i, _)), None) => Some(i),
+            (Some(_), Some(_)) => None,
+            (None, _) => self.columns.iter().position(|(table, column)| {
+                name.strip_prefix(table.as_str())
+                    .and_then(|name| name.strip_prefix('.'))
+                    == Some(column)
+            }),
+        }
+    }
+
+    /// Whether several tables have a column with the key `name`
+    fn is_ambiguous(&self, name: &str) -> bool {
+        self.columns
+            .iter()
+            .filter(|(_, column)| column == name)
+            .count()
+            > 1
+    }
+
+    /// Names of the result columns of `SELECT *`, qualified only if needed
+    fn wildcard_names(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|(table, column)| {
+                if self.is_ambiguous(column) {
+                    format!("{table}.{column}")
+                } else {
+                    column.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Row of the result with the values used for sorting it
+struct ResultRow {
+    values: Vec<Value>,
+    sort_keys: Vec<Value>,
+}
+
+impl Query {
+    /// Parse a query string
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        Ok(parser::parse(query)?)
+    }
+
+    /// Execute the query against the tables of the `catalog`
+    pub fn execute(&self, catalog: &Catalog<'_>) -> Result<HashTable<String, Value>, QueryError> {
+        let mut source = SourceColumns::default();
+        self.execute_with(catalog, &mut source)
+            .map_err(|error| match error {
+                QueryError::Expr(ExprError::UnknownColumn(name)) if source.is_ambiguous(&name) => {
+                    QueryError::AmbiguousColumn(name)
+                }
+                error => error,
+            })
+    }
+
+    /// Execute the query, adding the columns of the joined tables to `source`
+    fn execute_with(
+        &self,
+        catalog: &Catalog<'_>,
+        source: &mut SourceColumns,
+    ) -> Result<HashTable<String, Value>, QueryError> {
+        let joined_rows = self.join(catalog, source)?;
+        let source = &*source;
+        let source_columns = source.wildcard_names();
+
+        let columns = self.result_columns(&source_columns)?;
+
+        let lookup_source = |row: &[Value], name: &str| source.index(name).map(|i| row[i].clone());
+
+        let mut rows = Vec::new();
+        for row in &joined_rows {
+            if let Some(filter) = &self.filter {
+                if !truthy(
+                    "condition",
+                    &filter.eval_with(&|name| lookup_source(row, name))?,
+                )? {
+                    continue;
+                }
+            }
+            rows.push(&**row);
+        }
+
+        let is_aggregate = !self.group_by.is_empty()
+            || self
+                .select
+                .iter()
+                .any(|item| matches!(item, SelectItem::Aggregate { .. }));
+
+        let groups: Vec<Vec<&[Value]>> = if is_aggregate {
+            self.group(&rows, &lookup_source)?
+        } else {
+            rows.into_iter().map(|row| vec![row]).collect()
+        };
+
+        let mut result_rows = Vec::with_capacity(groups.len());
+        for group in groups {
+            let values = self.evaluate_items(&group, &source_columns, &lookup_source)?;
+            let first = group.first().copied();
+            let lookup = |name: &str| {
+                columns
+                    .iter()
+                    .position(|column| column == name)
+                    .map(|i| values[i].clone())
+                    .or_else(|| lookup_source(first?, name))
+            };
+            let sort_keys = self
+                .order_by
+                .iter()
+                .map(|(expr, _)| expr.eval_with(&lookup))
+                .collect::<Result<_, _>>()?;
+            result_rows.push(ResultRow { values, sort_keys });
+        }
+
+        result_rows.sort_by(|a, b| {
+            self.order_by
+                .iter()
+                .zip(a.sort_keys.iter().zip(&b.sort_keys))
+                .map(|((_, descending), (a, b))| {
+                    let ordering = a.total_cmp(b);
+                    if *descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let limit = self.limit.unwrap_or(usize::MAX);
+        let mut result =
+            HashTable::with_columns_and_capacity(columns, result_rows.len().min(limit));
+        for row in result_rows.into_iter().skip(self.offset).take(limit) {
+            result.values_vector.extend(row.values);
+        }
+        Ok(result)
+    }
+
+    /// Rows of the `FROM` table joined with the tables of the `JOIN` clauses, adding the columns
+    /// of every table to `source`
+    fn join<'t>(
+        &self,
+        catalog: &Catalog<'t>,
+        source: &mut SourceColumns,
+    ) -> Result<Vec<Cow<'t, [Value]>>, QueryError> {
+        let tables: Vec<&TableRef> = std::iter::once(&self.from)
+            .chain(self.joins.iter().map(|join| &join.table))
+            .collect();
+        for (i, table) in tables.iter().enumerate() {
+            if tables[..i].iter().any(|other| other.name() == table.name()) {
+                return Err(QueryError::DuplicateTable(table.name().to_owned()));
+            }
+        }
+        let get_table = |table: &TableRef| {
+            catalog
+                .tables
+                .get(&table.table)
+                .copied()
+                .ok_or_else(|| QueryError::UnknownTable(table.table.clone()))
+        };
+
+        let from = get_table(&self.from)?;
+        source.add(self.from.name(), from);
+        let mut rows: Vec<Cow<'t, [Value]>> = from
+            .iter()
+            .map(|row| Cow::Borrowed(row.row_values))
+            .collect();
+        for join in &self.joins {
+            let table = get_table(&join.table)?;
+            source.add(join.table.name(), table);
+            let mut joined_rows = Vec::new();
+            for left in &rows {
+                let mut matched = false;
+                for right in table {
+                    let row: Vec<Value> = left.iter().chain(right.row_values).cloned().collect();
+                    let on = join
+                        .on
+                        .eval_with(&|name| source.index(name).map(|i| row[i].clone()))?;
+                    if truthy("condition", &on)? {
+                        joined_rows.push(Cow::Owned(row));
+                        matched = true;
+                    }
+                }
+                if !matched && join.kind == JoinKind::Left {
+                    let nulls = std::iter::repeat_n(Value::Null, table.columns_len());
+                    joined_rows.push(Cow::Owned(left.iter().cloned().chain(nulls).collect()));
+                }
+            }
+            rows = joined_rows;
+        }
+        Ok(rows)
+    }
+
+    /// Names of the result columns in order
+    fn result_columns(&self, source_columns: &[String]) -> Result<Vec<String>, QueryError> {
+        let mut columns: Vec<String> = Vec::new();
+        for item in &self.select {
+            let names = match item {
+                SelectItem::Wildcard => source_columns.to_vec(),
+                SelectItem::Expr {
+                    alias: Some(alias), ..
+                }
+                | SelectItem::Aggregate {
+                    alias: Some(alias), ..
+                } => vec![alias.clone()],
+                SelectItem::Expr {
+                    expr: Expr::Column(name),
+                    alias: None,
+                } => vec![name.clone()],
+                SelectItem::Expr { expr, alias: None } => vec![expr.to_string()],
+                SelectItem::Aggregate {
+                    function,
+                    argument,
+                    alias: None,
+                } => vec![match argument {
+                    Some(argument) => format!("{function}({argument})"),
+                    None => format!("{function}(*)"),
+                }],
+            };
+            for name in names {
+                if columns.contains(&name) {
+                    return Err(QueryError::DuplicateColumn(name));
+                }
+                columns.push(name);
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Split rows into groups with equal values of the `GROUP BY` expressions.
+    ///
+    /// Without `GROUP BY` all rows form a single group, even if there are none.
+    fn group<'r>(
+        &self,
+        rows: &[&'r [Value]],
+        lookup: &impl Fn(&[Value], &str) -> Option<Value>,
+    ) -> Result<Vec<Vec<&'r [Value]>>, QueryError> {
+        if self.group_by.is_empty() {
+            return Ok(vec![rows.to_vec()]);
+        }
+        let keys = rows
+            .iter()
+            .map(|row| {
+                self.group_by
+                    .iter()
+                    .map(|expr| expr.eval_with(&|name| lookup(row, name)))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let compare_keys = |a: &[Value], b: &[Value]| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| a.total_cmp(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        };
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        order.sort_by(|a, b| compare_keys(&keys[*a], &keys[*b]));
+
+        let mut groups: Vec<Vec<&[Value]>> = Vec::new();
+        let mut previous: Option<usize> = None;
+        for i in order {
+            match previous {
+                Some(p) if compare_keys(&keys[p], &keys[i]).is_eq() => {
+                    groups.last_mut().unwrap().push(rows[i])
+                }
+                _ => groups.push(vec![rows[i]]),
+            }
+            previous = Some(i);
+        }
+        Ok(groups)
+    }
+
+    /// Values of the select items for a group of rows
+    fn evaluate_items(
+        &self,
+        group: &[&[Value]],
+        source_columns: &[String],
+        lookup: &impl Fn(&[Value], &str) -> Option<Value>,
+    ) -> Result<Vec<Value>, QueryError> {
+        let first = group.first().copied();
+        let eval_first = |expr: &Expr| match first {
+            Some(row) => expr.eval_with(&|name| lookup(row, name)),
+            None => Ok(Value::Null),
+        };
+        let mut values = Vec::new();
+        for item in &self.select {
+            match item {
+                SelectItem::Wildcard => match first {
+                    Some(row) => values.extend(row.iter().cloned()),
+                    None => values.extend(source_columns.iter().map(|_| Value::Null)),
+                },
+                SelectItem::Expr { expr, .. } => values.push(eval_first(expr)?),
+                SelectItem::Aggregate {
+                    function, argument, ..
+                } => {
+                    let arguments = group
+                        .iter()
+                        .map(|row| match argument {
+                            Some(expr) => expr.eval_with(&|name| lookup(row, name)),
+                            None => Ok(Value::Bool(true)),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    values.push(aggregate(*function, arguments)?);
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// Compute an aggregate function over values, ignoring nulls
+fn aggregate(function: Aggregate, values: Vec<Value>) -> Result<Value, QueryError> {
+    let values: Vec<Value> = values.into_iter().filter(|v| !v.is_null()).collect();
+    let type_error = |value: &Value| QueryError::Aggregate {
+        function,
+        value_type: value.type_name(),
+    };
+    match function {
+        Aggregate::Count => Ok(Value::from(values.len())),
+        Aggregate::Min => Ok(values
+            .into_iter()
+            .min_by(Value::total_cmp)
+            .unwrap_or(Value::Null)),
+        Aggregate::Max => Ok(values
+            .into_iter()
+            .max_by(Value::total_cmp)
+            .unwrap_or(Value::Null)),
+        Aggregate::Sum | Aggregate::Avg => {
+            if values.is_empty() {
+                return Ok(Value::Null);
+            }
+            let mut int_sum: Option<i64> = Some(0);
+            let mut float_sum = 0.0;
+            for value in &values {
+                match value {
+                    Value::Int(i) => int_sum = int_sum.and_then(|sum| sum.checked_add(*i)),
+                    Value::Float(_) => int_sum = None,
+                    other => return Err(type_error(other)),
+                }
+                float_sum += value.as_f64().unwrap();
+            }
+            Ok(match (function, int_sum) {
+                (Aggregate::Sum, Some(sum)) => Value::Int(sum),
+                (Aggregate::Sum, None) => Value::Float(float_sum),
+                _ => Value::Float(float_sum / values.len() as f64),
+            })
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Parser of the query syntax
+
+use super::{Aggregate, Join, JoinKind, Query, SelectItem, TableRef};
+use crate::expr::{parser::Parser, ExprError};
+
+fn aggregate_function(name: &str) -> Option<Aggregate> {
+    match name {
+        "count" => Some(Aggregate::Count),
+        "sum" => Some(Aggregate::Sum),
+        "avg" => Some(Aggregate::Avg),
+        "min" => Some(Aggregate::Min),
+        "max" => Some(Aggregate::Max),
+        _ => None,
+    }
+}
+
+fn alias(parser: &mut Parser) -> Result<Option<String>, ExprError> {
+    if parser.eat_keyword("as") {
+        parser.name().map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Table name with an optional alias
+fn table_ref(parser: &mut Parser) -> Result<TableRef, ExprError> {
+    let table = parser.name()?;
+    let alias = match alias(parser)? {
+        Some(alias) => Some(alias),
+        None => parser.eat_bare_name(),
+    };
+    Ok(TableRef { table, alias })
+}
+
+/// Kind of the next `JOIN` clause, if there is one
+fn join_kind(parser: &mut Parser) -> Result<Option<JoinKind>, ExprError> {
+    let kind = if parser.eat_keyword("join") {
+        return Ok(Some(JoinKind::Inner));
+    } else if parser.eat_keyword("inner") {
+        JoinKind::Inner
+    } else if parser.eat_keyword("left") {
+        parser.eat_keyword("outer");
+        JoinKind::Left
+    } else {
+        return Ok(None);
+    };
+    parser.expect_keyword("join")?;
+    Ok(Some(kind))
+}
+
+fn select_item(parser: &mut Parser) -> Result<SelectItem, ExprError> {
+    if parser.eat_op(&["*"]).is_some() {
+        return Ok(SelectItem::Wildcard);
+    }
+    if let Some(function) = parser
+        .eat_function(&["count", "sum", "avg", "min", "max"])
+        .and_then(aggregate_function)
+    {
+        let argument = if function == Aggregate::Count && parser.eat_op(&["*"]).is_some() {
+            None
+        } else {
+            Some(parser.expr()?)
+        };
+        parser.expect_rparen()?;
+        return Ok(SelectItem::Aggregate {
+            function,
+            argument,
+            alias: alias(parser)?,
+        });
+    }
+    Ok(SelectItem::Expr {
+        expr: parser.expr()?,
+        alias: alias(parser)?,
+    })
+}
+
+pub(super) fn parse(source: &str) -> Result<Query, ExprError> {
+    let mut parser = Parser::new(source, true)?;
+
+    parser.expect_keyword("select")?;
+    let mut select = vec![select_item(&mut parser)?];
+    while parser.eat_comma() {
+        select.push(select_item(&mut parser)?);
+    }
+
+    parser.expect_keyword("from")?;
+    let from = table_ref(&mut parser)?;
+    let mut joins = Vec::new();
+    while let Some(kind) = join_kind(&mut parser)? {
+        let table = table_ref(&mut parser)?;
+        parser.expect_keyword("on")?;
+        joins.push(Join {
+            kind,
+            table,
+            on: parser.expr()?,
+        });
+    }
+
+    let filter = if parser.eat_keyword("where") {
+        Some(parser.expr()?)
+    } else {
+        None
+    };
+
+    let mut group_by = Vec::new();
+    if parser.eat_keyword("group") {
+        parser.expect_keyword("by")?;
+        group_by.push(parser.expr()?);
+        while parser.eat_comma() {
+            group_by.push(parser.expr()?);
+        }
+    }
+
+    let mut order_by = Vec::new();
+    if parser.eat_keyword("order") {
+        parser.expect_keyword("by")?;
+        loop {
+            let expr = parser.expr()?;
+            let descending = parser.eat_keyword("desc");
+            if !descending {
+                parser.eat_keyword("asc");
+            }
+            order_by.push((expr, descending));
+            if !parser.eat_comma() {
+                break;
+            }
+        }
+    }
+
+    let mut limit = None;
+    let mut offset = 0;
+    if parser.eat_keyword("limit") {
+        limit = Some(parser.integer()?);
+        if parser.eat_keyword("offset") {
+            offset = parser.integer()?;
+        }
+    }
+
+    if !parser.at_end() {
+        return Err(ExprError::Parse {
+            position: parser.offset(),
+            message: "unexpected trailing input".to_owned(),
+        });
+    }
+
+    Ok(Query {
+        select,
+        from,
+        joins,
+        filter,
+        group_by,
+        order_by,
+        limit,
+        offset,
+    })
+}
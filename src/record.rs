@@ -0,0 +1,289 @@
+//! Conversion between Rust structs and table rows
+//!
+//! A struct implementing [`TableRow`] maps each field to a column keyed by the field name. The
+//! trait is usually implemented with `#[derive(TableRow)]`, which requires the `derive` feature.
+//! A field can be stored under another key with `#[table(rename = "key")]`, and left out of the
+//! table with `#[table(skip)]`, in which case it's set to its [`Default`] value when read.
+//!
+//! Field values are converted through [`ToTableValue`] and [`FromTableValue`], so the same
+//! struct can be stored in a table where every value has the field type, such as
+//! `HashTable<&str, f64>` for a struct of `f64` fields, and in a table of [`Value`]s.
+//!
+//! ## Example
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! # use hash_table_datastruct::{HashTable, record::TableRow, value::Value};
+//! #[derive(Debug, PartialEq, TableRow)]
+//! struct Person {
+//!     #[table(rename = "full_name")]
+//!     name: String,
+//!     age: i64,
+//!     #[table(skip)]
+//!     visits: u32,
+//! }
+//!
+//! let people = [
+//!     Person { name: "Ann".to_owned(), age: 31, visits: 2 },
+//!     Person { name: "Bob".to_owned(), age: 45, visits: 0 },
+//! ];
+//! let mut table = HashTable::<&str, Value>::from_records(people);
+//! assert_eq!(table.get(&"full_name", 1), Some(&Value::from("Bob")));
+//!
+//! table.push_record(&Person { name: "Eve".to_owned(), age: 28, visits: 5 });
+//! let ages: Vec<i64> = table.records::<Person>().map(|person| person.unwrap().age).collect();
+//! assert_eq!(ages, [31, 45, 28]);
+//! ```
+
+use std::{borrow::Borrow, fmt, iter::FusedIterator, marker::PhantomData};
+
+#[cfg(feature = "derive")]
+pub use hash_table_datastruct_derive::TableRow;
+
+use crate::{
+    row::borrowed::HashTableRowBorrowed,
+    table::iter::HashTableBorrowedIter,
+    typedefs::*,
+    value::{ToValue, Value},
+    HashTable,
+};
+
+/// Error of reading a struct from a table row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordError {
+    /// The row has no column with this key
+    MissingColumn(&'static str),
+    /// The value in the column with this key can't be converted to the type of the field
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumn(column) => write!(f, "column {column:?} is missing"),
+            Self::InvalidValue(column) => {
+                write!(f, "value in column {column:?} has an unexpected type")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+/// A struct that can be stored as a row of a table with values of type `V`.
+///
+/// See the [module documentation](self) for details.
+pub trait TableRow<V>: Sized {
+    /// Keys of the columns the fields are stored in, in field order
+    const COLUMNS: &'static [&'static str];
+
+    /// Convert the fields into values, in the order of [`Self::COLUMNS`]
+    fn to_row(&self) -> Vec<V>;
+
+    /// Read the fields from a row of a table
+    fn from_row<K>(row: HashTableRowBorrowed<'_, K, V>) -> Result<Self, RecordError>
+    where
+        K: Borrow<str> + Hash + Eq;
+}
+
+/// Conversion of a field into a table value
+pub trait ToTableValue<V> {
+    /// Convert the field into a table value
+    fn to_table_value(&self) -> V;
+}
+
+/// Conversion of a table value into a field
+pub trait FromTableValue<V>: Sized {
+    /// Convert the table value into a field, returning `None` if it has an unexpected type
+    fn from_table_value(value: &V) -> Option<Self>;
+}
+
+impl<V: Clone> ToTableValue<V> for V {
+    fn to_table_value(&self) -> V {
+        self.clone()
+    }
+}
+
+impl<V: Clone> FromTableValue<V> for V {
+    fn from_table_value(value: &V) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+macro_rules! impl_value_field {
+    ($($t:ty),* $(,)?) => {$(
+        impl ToTableValue<Value> for $t {
+            fn to_table_value(&self) -> Value {
+                self.to_value()
+            }
+        }
+    )*};
+}
+
+impl_value_field!(bool, i8, i16, i32, i64, u8, u16, u32, u64, usize, isize, f32, f64, String);
+
+macro_rules! impl_value_int_field {
+    ($($t:ty),* $(,)?) => {$(
+        /// Integral floats are accepted if they are in range, as integers that don't fit into an
+        /// [`i64`] are stored as [`Value::Float`].
+        impl FromTableValue<Value> for $t {
+            fn from_table_value(value: &Value) -> Option<Self> {
+                match value {
+                    Value::Int(i) => (*i).try_into().ok(),
+                    // Saturates for floats outside of the `i128` range, which are out of range for
+                    // every field type too
+                    Value::Float(f) if f.fract() == 0.0 => (*f as i128).try_into().ok(),
+                    _ => None,
+                }
+            }
+        }
+    )*};
+}
+
+impl_value_int_field!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize);
+
+/// Integers are converted to floats.
+impl FromTableValue<Value> for f64 {
+    fn from_table_value(value: &Value) -> Option<Self> {
+        value.as_f64()
+    }
+}
+
+/// Integers are converted to floats.
+impl FromTableValue<Value> for f32 {
+    fn from_table_value(value: &Value) -> Option<Self> {
+        value.as_f64().map(|f| f as f32)
+    }
+}
+
+impl FromTableValue<Value> for bool {
+    fn from_table_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl FromTableValue<Value> for String {
+    fn from_table_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Str(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// [`None`] is stored as [`Value::Null`].
+impl<T: ToTableValue<Value>> ToTableValue<Value> for Option<T> {
+    fn to_table_value(&self) -> Value {
+        self.as_ref().map_or(Value::Null, T::to_table_value)
+    }
+}
+
+/// [`Value::Null`] is read as [`None`].
+impl<T: FromTableValue<Value>> FromTableValue<Value> for Option<T> {
+    fn from_table_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(None),
+            value => T::from_table_value(value).map(Some),
+        }
+    }
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: Borrow<str> + Hash + Eq,
+{
+    /// Create a table with a row for each record.
+    ///
+    /// The columns are the [`TableRow::COLUMNS`] of the record type.
+    pub fn from_records<T, I>(records: I) -> Self
+    where
+        T: TableRow<V>,
+        I: IntoIterator<Item = T>,
+        K: From<&'static str>,
+    {
+        let mut table = Self::with_columns(T::COLUMNS.iter().map(|key| K::from(*key)));
+        for record in records {
+            table.push_record(&record);
+        }
+        table
+    }
+
+    /// Add a record as a row of the table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the columns of the table are not the [`TableRow::COLUMNS`] of the record type.
+    pub fn push_record<T>(&mut self, record: &T)
+    where
+        T: TableRow<V>,
+    {
+        assert!(
+            self.columns_len() == T::COLUMNS.len()
+                && T::COLUMNS
+                    .iter()
+                    .all(|key| self.indices_table.contains_key(*key)),
+            "Table must have a column for every field of the record"
+        );
+        let mut values: Vec<Option<V>> = self.indices_table.iter().map(|_| None).collect();
+        for (key, value) in T::COLUMNS.iter().zip(record.to_row()) {
+            values[self.indices_table[*key]] = Some(value);
+        }
+        self.values_vector.extend(
+            values
+                .into_iter()
+                .map(|value| value.expect("Columns are unique")),
+        );
+    }
+
+    /// Iterate over the rows of the table converted to records.
+    pub fn records<T>(&self) -> RecordIter<'_, K, V, T>
+    where
+        T: TableRow<V>,
+    {
+        RecordIter {
+            rows: self.iter(),
+            record: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the rows of a table converted to records.
+///
+/// Returned by [`HashTable::records`].
+#[derive(Debug)]
+pub struct RecordIter<'t, K, V, T> {
+    rows: HashTableBorrowedIter<'t, K, V>,
+    record: PhantomData<fn() -> T>,
+}
+
+impl<K, V, T> Iterator for RecordIter<'_, K, V, T>
+where
+    K: Borrow<str> + Hash + Eq,
+    T: TableRow<V>,
+{
+    type Item = Result<T, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(T::from_row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<K, V, T> FusedIterator for RecordIter<'_, K, V, T>
+where
+    K: Borrow<str> + Hash + Eq,
+    T: TableRow<V>,
+{
+}
+
+impl<K, V, T> ExactSizeIterator for RecordIter<'_, K, V, T>
+where
+    K: Borrow<str> + Hash + Eq,
+    T: TableRow<V>,
+{
+}
@@ -31,6 +31,63 @@ where
     {
         self.indices_table.get(column).map(|i| &self.row_values[*i])
     }
+
+    /// Get elements of the row in several columns at once.
+    ///
+    /// Returns None if a `column` is missing.
+    pub fn get_many<Q, const N: usize>(&self, columns: [&Q; N]) -> Option<[&'t V; N]>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut indices = [0; N];
+        for (index, column) in indices.iter_mut().zip(columns) {
+            *index = *self.indices_table.get(column)?;
+        }
+        Some(indices.map(|i| &self.row_values[i]))
+    }
+
+    /// Returns `true` if the row has a value in the `column`
+    pub fn contains_column<Q>(&self, column: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.indices_table.contains_key(column)
+    }
+
+    /// Clone the row into a map from column keys to values
+    pub fn to_hashmap(&self) -> HashMap<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.into_iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+/// Rows are equal if they have the same columns with equal values, even if they belong to tables
+/// with different column orders.
+impl<'a, 'b, K, V> PartialEq<HashTableRowBorrowed<'b, K, V>> for HashTableRowBorrowed<'a, K, V>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &HashTableRowBorrowed<'b, K, V>) -> bool {
+        self.columns_len() == other.columns_len()
+            && self
+                .into_iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K, V> Eq for HashTableRowBorrowed<'_, K, V>
+where
+    K: Hash + Eq,
+    V: Eq,
+{
 }
 
 impl<'t, K, V> HashTableRowBorrowed<'t, K, V> {
@@ -43,6 +100,24 @@ impl<'t, K, V> HashTableRowBorrowed<'t, K, V> {
     pub fn columns_len(&self) -> usize {
         self.indices_table.len()
     }
+
+    /// Return an iterator over the values of the row in column-index order
+    pub fn values(&self) -> std::slice::Iter<'t, V> {
+        self.row_values.iter()
+    }
+
+    /// Get the element of the row in the column with the index `index`
+    pub fn get_index(&self, index: usize) -> Option<&'t V> {
+        self.row_values.get(index)
+    }
+
+    /// Clone the values of the row in column-index order
+    pub fn to_vec(&self) -> Vec<V>
+    where
+        V: Clone,
+    {
+        self.row_values.to_vec()
+    }
 }
 
 impl<'t, K, V> IntoIterator for HashTableRowBorrowed<'t, K, V> {
@@ -11,9 +11,12 @@ pub struct HashTableMutableBorrowedRow<'t, K, V> {
     pub(crate) values: &'t mut [V],
 }
 
-impl<'t, 'r: 't, K, V> HashTableMutableBorrowedRow<'t, K, V> {
+impl<K, V> HashTableMutableBorrowedRow<'_, K, V> {
     /// Get an element of this row in the requested `column`.
-    pub fn get<Q>(&'r mut self, column: &Q) -> Option<&mut V>
+    ///
+    /// The value borrows the row only while it's used, so the row can be accessed again
+    /// afterwards. Use [`get_many_mut`](Self::get_many_mut) to hold several values at once.
+    pub fn get<Q>(&mut self, column: &Q) -> Option<&mut V>
     where
         K: Borrow<Q>,
         K: Hash + Eq,
@@ -21,6 +24,22 @@ impl<'t, 'r: 't, K, V> HashTableMutableBorrowedRow<'t, K, V> {
     {
         self.indices_table.get(column).map(|i| &mut self.values[*i])
     }
+
+    /// Get mutable references to elements of this row in several columns at once.
+    ///
+    /// Returns None if a `column` is missing or the same column is requested more than once.
+    pub fn get_many_mut<Q, const N: usize>(&mut self, columns: [&Q; N]) -> Option<[&mut V; N]>
+    where
+        K: Borrow<Q>,
+        K: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut indices = [0; N];
+        for (index, column) in indices.iter_mut().zip(columns) {
+            *index = *self.indices_table.get(column)?;
+        }
+        self.values.get_disjoint_mut(indices).ok()
+    }
 }
 
 impl<'t, K, V> IntoIterator for HashTableMutableBorrowedRow<'t, K, V> {
@@ -0,0 +1,349 @@
+//! Descriptive statistics of columns
+//!
+//! Floating point values that are NaN are treated as missing: they are skipped by every
+//! statistic except [`HashTable::column_sum`].
+//!
+//! ## Example
+//! ```
+//! # use hash_table_datastruct::{HashTable, stats::DESCRIBE_ROWS};
+//! let table = HashTable::from_column_keys_and_rows(["x", "y"], [[1, 10], [2, 20], [3, 60]]);
+//! assert_eq!(table.column_sum(&"y"), Some(90));
+//! assert_eq!(table.column_mean(&"x"), Some(2.0));
+//! assert_eq!(table.column_min_max(&"y"), Some((10, 60)));
+//!
+//! let description = table.describe();
+//! let median = DESCRIBE_ROWS.iter().position(|s| *s == "50%").unwrap();
+//! assert_eq!(description.get(&"y", median), Some(&20.0));
+//! ```
+
+use std::{
+    borrow::Borrow,
+    iter::Sum,
+    ops::{Add, Mul, Sub},
+};
+
+use crate::{typedefs::*, HashTable};
+
+/// Names of the rows of the table returned by [`HashTable::describe`], in row order
+pub const DESCRIBE_ROWS: [&str; 8] = ["count", "mean", "std", "min", "25%", "50%", "75%", "max"];
+
+/// Primitive numbers that statistics can be computed for
+pub trait Numeric:
+    Copy + PartialOrd + Sum<Self> + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// Convert the value to a `f64`, possibly losing precision
+    fn to_f64(self) -> f64;
+
+    /// Add the values, returning `None` if the result overflows
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Subtract the values, returning `None` if the result overflows
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Multiply the values, returning `None` if the result overflows
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /// Returns `true` if the value is a missing value
+    fn is_missing(self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_numeric_integer {
+    ($($t:ty),* $(,)?) => {$(
+        impl Numeric for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    )*};
+}
+
+impl_numeric_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_numeric_float {
+    ($($t:ty),* $(,)?) => {$(
+        impl Numeric for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            /// Floats don't overflow, they become infinite.
+            fn checked_add(self, other: Self) -> Option<Self> {
+                Some(self + other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                Some(self - other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                Some(self * other)
+            }
+
+            fn is_missing(self) -> bool {
+                self.is_nan()
+            }
+        }
+    )*};
+}
+
+impl_numeric_float!(f32, f64);
+
+/// A range of values and the number of values in it, returned by
+/// [`HashTable::column_histogram`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramBin {
+    /// Smallest value of the bin
+    pub start: f64,
+    /// End of the bin, only included in the last bin
+    pub end: f64,
+    /// Number of values in the bin
+    pub count: usize,
+}
+
+/// Quantile of sorted values with linear interpolation between the closest values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// Statistics of a column in the order of [`DESCRIBE_ROWS`]
+fn describe_values(mut values: Vec<f64>) -> [f64; 8] {
+    values.sort_unstable_by(f64::total_cmp);
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1.0);
+    [
+        count,
+        mean,
+        variance.sqrt(),
+        quantile(&values, 0.0),
+        quantile(&values, 0.25),
+        quantile(&values, 0.5),
+        quantile(&values, 0.75),
+        quantile(&values, 1.0),
+    ]
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Iterate over the values of a column.
+    fn column_values<Q>(&self, column: &Q) -> Option<impl Iterator<Item = &V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.column_index(column)?;
+        Some(
+            self.values_vector
+                .iter()
+                .skip(index)
+                .step_by(self.columns_len()),
+        )
+    }
+
+    /// Values of a column that are not missing, converted to `f64`.
+    fn column_numbers<Q>(&self, column: &Q) -> Option<Vec<f64>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        Some(
+            self.column_values(column)?
+                .filter(|value| !value.is_missing())
+                .map(|value| value.to_f64())
+                .collect(),
+        )
+    }
+
+    /// Compute the statistics of every column.
+    ///
+    /// The returned table has the same columns as this table and a row for each statistic in
+    /// [`DESCRIBE_ROWS`]: the number of values, mean, sample standard deviation, minimum,
+    /// quartiles and maximum. Quartiles are interpolated linearly between the closest values.
+    /// Statistics that are not defined for a column, such as the mean of a column without values,
+    /// are NaN.
+    pub fn describe(&self) -> HashTable<K, f64>
+    where
+        K: Clone,
+        V: Numeric,
+    {
+        let columns = self.column_keys_in_order();
+        let statistics: Vec<[f64; 8]> = columns
+            .iter()
+            .map(|key| {
+                describe_values(
+                    self.column_numbers::<K>(key)
+                        .expect("Column is in the table"),
+                )
+            })
+            .collect();
+        HashTable::from_column_keys_and_rows(
+            columns.into_iter().cloned(),
+            (0..DESCRIBE_ROWS.len()).map(|row| {
+                statistics
+                    .iter()
+                    .map(|column| column[row])
+                    .collect::<Vec<_>>()
+            }),
+        )
+    }
+
+    /// Sum the values of a column.
+    ///
+    /// Returns `None` if there is no such column or if the sum of an integer column overflows.
+    pub fn column_sum<Q>(&self, column: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        // The sum of no values is zero
+        let zero = std::iter::empty().sum();
+        self.column_values(column)?
+            .try_fold(zero, |sum: V, value| sum.checked_add(*value))
+    }
+
+    /// Compute the mean of the values of a column.
+    ///
+    /// Returns `None` if there is no such column or the column has no values.
+    pub fn column_mean<Q>(&self, column: &Q) -> Option<f64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        let values = self.column_numbers(column)?;
+        if values.is_empty() {
+            return None;
+        }
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+
+    /// Find the smallest and the largest value of a column.
+    ///
+    /// Returns `None` if there is no such column or the column has no values.
+    pub fn column_min_max<Q>(&self, column: &Q) -> Option<(V, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        let mut values = self
+            .column_values(column)?
+            .copied()
+            .filter(|value| !value.is_missing());
+        let first = values.next()?;
+        Some(values.fold((first, first), |(min, max), value| {
+            (
+                if value < min { value } else { min },
+                if value > max { value } else { max },
+            )
+        }))
+    }
+
+    /// Count the values of a column in `bins` ranges of equal width between the smallest and the
+    /// largest value.
+    ///
+    /// Returns `None` if there is no such column or the column has no values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bins` is 0.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let table = HashTable::from_column_keys_and_rows(["x"], [[0.0], [1.0], [2.5], [4.0]]);
+    /// let histogram = table.column_histogram(&"x", 2).unwrap();
+    /// assert_eq!(histogram[0].end, 2.0);
+    /// assert_eq!(histogram.iter().map(|bin| bin.count).collect::<Vec<_>>(), [2, 2]);
+    /// ```
+    pub fn column_histogram<Q>(&self, column: &Q, bins: usize) -> Option<Vec<HistogramBin>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        assert!(bins > 0, "Histogram must have at least one bin");
+        let values = self.column_numbers(column)?;
+        let (min, max) = values.iter().fold(None, |range, value| match range {
+            None => Some((*value, *value)),
+            Some((min, max)) => Some((value.min(min), value.max(max))),
+        })?;
+        let width = (max - min) / bins as f64;
+        let mut histogram: Vec<HistogramBin> = (0..bins)
+            .map(|bin| HistogramBin {
+                start: min + width * bin as f64,
+                end: if bin + 1 == bins {
+                    max
+                } else {
+                    min + width * (bin + 1) as f64
+                },
+                count: 0,
+            })
+            .collect();
+        for value in values {
+            let bin = if width > 0.0 {
+                (((value - min) / width) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            histogram[bin].count += 1;
+        }
+        Some(histogram)
+    }
+
+    /// Count how many times each value occurs in a column.
+    ///
+    /// The most common values come first, values that occur equally often are in the order of
+    /// their first occurrence. Returns `None` if there is no such column.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let table = HashTable::from_column_keys_and_rows(["pet"], [["cat"], ["dog"], ["dog"]]);
+    /// assert_eq!(table.value_counts(&"pet"), Some(vec![(&"dog", 2), (&"cat", 1)]));
+    /// ```
+    pub fn value_counts<Q>(&self, column: &Q) -> Option<Vec<(&V, usize)>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Hash + Eq,
+    {
+        let mut positions: HashMap<&V, usize> = HashMap::new();
+        let mut counts: Vec<(&V, usize)> = Vec::new();
+        for value in self.column_values(column)? {
+            match positions.get(value) {
+                Some(position) => counts[*position].1 += 1,
+                None => {
+                    positions.insert(value, counts.len());
+                    counts.push((value, 1));
+                }
+            }
+        }
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        Some(counts)
+    }
+}
@@ -0,0 +1,189 @@
+//! Combining several tables into one
+
+use crate::{typedefs::*, HashTable};
+
+impl<K, V> HashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Rearrange the row-major `values` of a table with `other_columns_len` columns by the
+    /// columns of this table.
+    ///
+    /// `source[i]` is the column index in the other table of the column with index `i` in this
+    /// table, or `None` if the values of the column are produced by `fill`.
+    fn rows_in_column_order<F>(
+        &self,
+        values: Vec<V>,
+        other_columns_len: usize,
+        source: &[Option<usize>],
+        mut fill: F,
+    ) -> Vec<V>
+    where
+        F: FnMut(&K) -> V,
+    {
+        let rows = values.len().checked_div(other_columns_len).unwrap_or(0);
+        let keys = self.column_keys_in_order();
+        if other_columns_len == keys.len() && source.iter().enumerate().all(|(i, j)| *j == Some(i))
+        {
+            return values;
+        }
+        let mut values: Vec<Option<V>> = values.into_iter().map(Some).collect();
+        let mut result = Vec::with_capacity(rows * keys.len());
+        for row in 0..rows {
+            for (key, j) in keys.iter().zip(source) {
+                result.push(match j {
+                    Some(j) => values[row * other_columns_len + j]
+                        .take()
+                        .expect("Each value is moved once"),
+                    None => fill(key),
+                });
+            }
+        }
+        result
+    }
+
+    /// Append the rows of `other` to this table.
+    ///
+    /// Columns are matched by their keys, so the tables may have different column orders. If this
+    /// table has no columns, it takes the columns of `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tables have different sets of columns. Use [`Self::append_table_union`] to
+    /// combine tables with different columns.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let mut monday = HashTable::from_column_keys_and_rows(["item", "sold"], [[1, 10], [2, 3]]);
+    /// let tuesday = HashTable::from_column_keys_and_rows(["sold", "item"], [[7, 1]]);
+    /// monday.append_table(tuesday);
+    /// assert_eq!(monday.rows_len(), 3);
+    /// assert_eq!(monday.get(&"sold", 2), Some(&7));
+    /// ```
+    pub fn append_table(&mut self, other: Self) {
+        if self.columns_len() == 0 {
+            *self = other;
+            return;
+        }
+        if other.columns_len() == 0 {
+            return;
+        }
+        assert!(
+            other.columns_len() == self.columns_len(),
+            "Appended table must have the same columns"
+        );
+        let mut source = vec![None; self.columns_len()];
+        for (key, j) in &other.indices_table {
+            let i = *self
+                .indices_table
+                .get(key)
+                .expect("Appended table must have the same columns");
+            source[i] = Some(*j);
+        }
+        let other_columns_len = other.columns_len();
+        let values =
+            self.rows_in_column_order(other.values_vector, other_columns_len, &source, |_| {
+                unreachable!("Every column has values in the appended table")
+            });
+        self.values_vector.extend(values);
+    }
+
+    /// Append the rows of `other` to this table, adding the columns that are only present in
+    /// `other`.
+    ///
+    /// Values of columns missing from one of the tables are produced by `fill` from the column
+    /// key. New columns are placed after the existing ones, in the column order of `other`.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let mut table = HashTable::from_column_keys_and_rows(["a", "b"], [[1, 2]]);
+    /// let other = HashTable::from_column_keys_and_rows(["c", "a"], [[30, 10]]);
+    /// table.append_table_union(other, |_| 0);
+    /// assert_eq!(table.column_keys_in_order(), [&"a", &"b", &"c"]);
+    /// assert_eq!(table.get(&"b", 1), Some(&0));
+    /// assert_eq!(table.get(&"c", 0), Some(&0));
+    /// assert_eq!(table.get(&"c", 1), Some(&30));
+    /// ```
+    pub fn append_table_union<F>(&mut self, other: Self, mut fill: F)
+    where
+        F: FnMut(&K) -> V,
+    {
+        let other_columns_len = other.columns_len();
+        let mut other_columns: Vec<(K, usize)> = other.indices_table.into_iter().collect();
+        other_columns.sort_unstable_by_key(|(_, j)| *j);
+
+        let rows = self.rows_len();
+        let mut source = vec![None; self.columns_len()];
+        let mut new_columns = Vec::new();
+        for (key, j) in other_columns {
+            match self.indices_table.get(&key) {
+                Some(i) => source[*i] = Some(j),
+                None => {
+                    let values: Vec<V> = (0..rows).map(|_| fill(&key)).collect();
+                    new_columns.push((key, values));
+                    source.push(Some(j));
+                }
+            }
+        }
+        self.insert_columns(new_columns);
+        let values =
+            self.rows_in_column_order(other.values_vector, other_columns_len, &source, fill);
+        self.values_vector.extend(values);
+    }
+
+    /// Stack the rows of the `tables` into one table.
+    ///
+    /// See [`Self::append_table`] for how the columns are matched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tables have different sets of columns.
+    pub fn concat<I>(tables: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let mut result = Self::default();
+        for table in tables {
+            result.append_table(table);
+        }
+        result
+    }
+
+    /// Stack the rows of the `tables` into one table with the columns of all of them.
+    ///
+    /// See [`Self::append_table_union`] for how the columns are matched and filled.
+    pub fn concat_union<I, F>(tables: I, mut fill: F) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+        F: FnMut(&K) -> V,
+    {
+        let mut result = Self::default();
+        for table in tables {
+            result.append_table_union(table, &mut fill);
+        }
+        result
+    }
+
+    /// Add all columns of `other` to this table, after the existing columns.
+    ///
+    /// If this table has no columns, it takes the columns of `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column of `other` is already present in this table or if the tables have
+    /// different amounts of rows.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let mut names = HashTable::from_column_keys_and_rows(["name"], [["Ann"], ["Bob"]]);
+    /// let ages = HashTable::from_column_keys_and_rows(["age"], [["31"], ["45"]]);
+    /// names.hstack(ages);
+    /// assert_eq!(names.get(&"age", 1), Some(&"45"));
+    /// ```
+    pub fn hstack(&mut self, other: Self) {
+        self.insert_columns(other.into_iter_columns().map(|column| column.into_pair()));
+    }
+}
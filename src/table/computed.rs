@@ -0,0 +1,495 @@
+//! Tables with lazily computed columns
+
+use std::{borrow::Borrow, cell::OnceCell, fmt, iter::FusedIterator, ops::Deref};
+
+use crate::{
+    row::borrowed::{BorrowedRowIter, HashTableRowBorrowed},
+    typedefs::*,
+    HashTable,
+};
+
+/// Generator function of a computed column
+type ColumnGenerator<K, V> = Box<dyn Fn(HashTableRowBorrowed<'_, K, V>) -> V>;
+
+/// A column whose values are produced from the stored values of the same row.
+struct ComputedColumn<K, V> {
+    generator: ColumnGenerator<K, V>,
+    /// `None` if the values are computed on every access.
+    ///
+    /// The cache of each row is created on first access and is reset whenever the stored values
+    /// may have changed.
+    cache: Option<OnceCell<Box<[OnceCell<V>]>>>,
+}
+
+impl<K, V> ComputedColumn<K, V> {
+    fn value(
+        &self,
+        row: HashTableRowBorrowed<'_, K, V>,
+        row_index: usize,
+        rows_len: usize,
+    ) -> ValueRef<'_, V> {
+        match &self.cache {
+            None => ValueRef::Owned((self.generator)(row)),
+            Some(cache) => {
+                let cells = cache.get_or_init(|| (0..rows_len).map(|_| OnceCell::new()).collect());
+                ValueRef::Borrowed(cells[row_index].get_or_init(|| (self.generator)(row)))
+            }
+        }
+    }
+
+    fn invalidate(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.take();
+        }
+    }
+}
+
+/// A value of a table that is either stored in the table or computed on access.
+#[derive(Debug)]
+pub enum ValueRef<'t, V> {
+    /// Value stored in the table or in the cache of a computed column
+    Borrowed(&'t V),
+    /// Value of an uncached computed column
+    Owned(V),
+}
+
+impl<V> Deref for ValueRef<'_, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Borrowed(v) => v,
+            Self::Owned(v) => v,
+        }
+    }
+}
+
+impl<V: Clone> ValueRef<'_, V> {
+    /// Take ownership of the value, cloning it if it's borrowed
+    pub fn into_owned(self) -> V {
+        match self {
+            Self::Borrowed(v) => v.clone(),
+            Self::Owned(v) => v,
+        }
+    }
+}
+
+/// A [`HashTable`] with additional columns that are computed from the stored columns of the same
+/// row on demand.
+///
+/// Computed columns are visible through [`Self::get`], [`Self::get_row`], [`Self::iter`] and
+/// serialization as if they were stored in the table, but they don't take up memory unless they
+/// are cached. A cached column stores each value after the first access to it.
+///
+/// Generator functions only see the stored columns of the row, not the other computed columns.
+///
+/// A stored column hides a computed column with the same key, for example one added through
+/// [`Self::table_mut`]. The computed column stays registered, but it is skipped by every method
+/// of the table and its rows.
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::{HashTable, table::computed::ComputedHashTable};
+/// let table = HashTable::from_column_keys_and_rows(["price", "amount"], [[3, 2], [5, 4]]);
+/// let mut table = ComputedHashTable::from(table);
+/// table.add_computed_column("total", |row| {
+///     row.get("price").unwrap() * row.get("amount").unwrap()
+/// });
+/// assert_eq!(*table.get("total", 1).unwrap(), 20);
+/// ```
+pub struct ComputedHashTable<K, V> {
+    table: HashTable<K, V>,
+    computed: HashMap<K, ComputedColumn<K, V>>,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ComputedHashTable<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComputedHashTable")
+            .field("table", &self.table)
+            .field("computed", &self.computed.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<K, V> From<HashTable<K, V>> for ComputedHashTable<K, V> {
+    fn from(table: HashTable<K, V>) -> Self {
+        Self {
+            table,
+            computed: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V> Default for ComputedHashTable<K, V> {
+    fn default() -> Self {
+        HashTable::default().into()
+    }
+}
+
+impl<K, V> ComputedHashTable<K, V> {
+    /// Returns the number of rows in this table.
+    #[inline]
+    pub fn rows_len(&self) -> usize {
+        self.table.rows_len()
+    }
+
+    /// Table of the stored values
+    #[inline]
+    pub fn table(&self) -> &HashTable<K, V> {
+        &self.table
+    }
+
+    /// Table of the stored values with mutable access.
+    ///
+    /// This resets the caches of all computed columns.
+    pub fn table_mut(&mut self) -> &mut HashTable<K, V> {
+        self.invalidate();
+        &mut self.table
+    }
+
+    /// Take the table of the stored values, dropping the computed columns
+    pub fn into_table(self) -> HashTable<K, V> {
+        self.table
+    }
+
+    /// Get the keys of the computed columns, including the ones hidden by stored columns
+    pub fn computed_column_keys(&self) -> impl ExactSizeIterator<Item = &K> + '_ {
+        self.computed.keys()
+    }
+
+    /// Get a row of the table.
+    ///
+    /// Returns None if `row` is bigger than or equal to the number of rows.
+    pub fn get_row(&self, row: usize) -> Option<ComputedRow<'_, K, V>> {
+        Some(ComputedRow {
+            stored: self.table.get_row(row)?,
+            computed: &self.computed,
+            row,
+            rows_len: self.rows_len(),
+        })
+    }
+
+    /// Row-wise iterator that borrows the table
+    #[inline]
+    pub fn iter(&self) -> ComputedHashTableIter<'_, K, V> {
+        ComputedHashTableIter {
+            row: 0,
+            table: self,
+        }
+    }
+
+    fn invalidate(&mut self) {
+        for column in self.computed.values_mut() {
+            column.invalidate();
+        }
+    }
+}
+
+impl<K, V> ComputedHashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Returns the number of columns in this table, including the computed ones.
+    pub fn columns_len(&self) -> usize {
+        self.table.columns_len() + visible_len(&self.table.indices_table, self.computed.keys())
+    }
+
+    /// Register a column whose values are computed from the row on access and cached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column with the same key already exists.
+    pub fn add_computed_column<F>(&mut self, column: K, generator: F)
+    where
+        F: Fn(HashTableRowBorrowed<'_, K, V>) -> V + 'static,
+    {
+        self.insert_computed(column, Box::new(generator), true)
+    }
+
+    /// Register a column whose values are computed from the row on every access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column with the same key already exists.
+    pub fn add_uncached_computed_column<F>(&mut self, column: K, generator: F)
+    where
+        F: Fn(HashTableRowBorrowed<'_, K, V>) -> V + 'static,
+    {
+        self.insert_computed(column, Box::new(generator), false)
+    }
+
+    fn insert_computed(&mut self, column: K, generator: ColumnGenerator<K, V>, cached: bool) {
+        if self.contains_column(&column) {
+            panic!("Column is already present in the table");
+        }
+        self.computed.insert(
+            column,
+            ComputedColumn {
+                generator,
+                cache: cached.then(OnceCell::new),
+            },
+        );
+    }
+
+    /// Unregister a computed column.
+    ///
+    /// Returns `false` if there is no computed column with this key.
+    pub fn remove_computed_column<Q>(&mut self, column: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.computed.remove(column).is_some()
+    }
+
+    /// Whether a stored or computed column with this key exists
+    pub fn contains_column<Q>(&self, column: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.table.indices_table.contains_key(column) || self.computed.contains_key(column)
+    }
+
+    /// Get an element from the table, computing it if it belongs to a computed column.
+    ///
+    /// Will return None if the `column` does not exist in the table or `row` is out of range.
+    pub fn get<Q>(&self, column: &Q, row: usize) -> Option<ValueRef<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_row(row)?.get(column)
+    }
+
+    /// Get a stored element from the table with mutable access.
+    ///
+    /// Values of computed columns can't be modified. If a value is returned, this resets the
+    /// caches of all computed columns.
+    pub fn get_mut<Q>(&mut self, column: &Q, row: usize) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let value = self.table.get_mut(column, row)?;
+        for column in self.computed.values_mut() {
+            column.invalidate();
+        }
+        Some(value)
+    }
+
+    /// Convert into a regular [`HashTable`], storing the values of all computed columns.
+    ///
+    /// Computed columns hidden by stored columns are dropped.
+    pub fn materialize(self) -> HashTable<K, V>
+    where
+        V: Clone,
+    {
+        let Self {
+            mut table,
+            computed,
+        } = self;
+        let rows_len = table.rows_len();
+        let columns: Vec<(K, Vec<V>)> = computed
+            .into_iter()
+            .filter(|(key, _)| !table.indices_table.contains_key(key))
+            .map(|(key, column)| {
+                let values = table
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| column.value(row, i, rows_len).into_owned())
+                    .collect();
+                (key, values)
+            })
+            .collect();
+        table.insert_columns(columns);
+        table
+    }
+}
+
+/// Number of computed columns that are not hidden by a stored column
+fn visible_len<'k, K>(
+    indices_table: &HashMap<K, usize>,
+    computed: impl Iterator<Item = &'k K>,
+) -> usize
+where
+    K: Hash + Eq + 'k,
+{
+    computed
+        .filter(|key| !indices_table.contains_key(*key))
+        .count()
+}
+
+/// A row of a [`ComputedHashTable`] that gives access to both stored and computed values
+pub struct ComputedRow<'t, K, V> {
+    stored: HashTableRowBorrowed<'t, K, V>,
+    computed: &'t HashMap<K, ComputedColumn<K, V>>,
+    row: usize,
+    rows_len: usize,
+}
+
+impl<K, V> Clone for ComputedRow<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for ComputedRow<'_, K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ComputedRow<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComputedRow")
+            .field("stored", &self.stored)
+            .field("row", &self.row)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'t, K, V> ComputedRow<'t, K, V>
+where
+    K: Hash + Eq,
+{
+    /// Get an element of the row in the requested `column`, computing it if needed
+    pub fn get<Q>(&self, column: &Q) -> Option<ValueRef<'t, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.stored.get(column) {
+            Some(value) => Some(ValueRef::Borrowed(value)),
+            None => self
+                .computed
+                .get(column)
+                .map(|c| c.value(self.stored, self.row, self.rows_len)),
+        }
+    }
+}
+
+impl<'t, K, V> ComputedRow<'t, K, V>
+where
+    K: Hash + Eq,
+{
+    /// Return an amount of columns in the row, including the computed ones
+    pub fn columns_len(&self) -> usize {
+        self.stored.columns_len() + visible_len(self.stored.indices_table, self.computed.keys())
+    }
+}
+
+impl<'t, K, V> ComputedRow<'t, K, V> {
+    /// Row of only the stored values
+    pub fn stored(&self) -> HashTableRowBorrowed<'t, K, V> {
+        self.stored
+    }
+}
+
+impl<'t, K, V> IntoIterator for ComputedRow<'t, K, V>
+where
+    K: Hash + Eq,
+{
+    type Item = (&'t K, ValueRef<'t, V>);
+    type IntoIter = ComputedRowIter<'t, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ComputedRowIter {
+            stored_iter: self.stored.into_iter(),
+            computed_iter: self.computed.iter(),
+            row: self.stored,
+            row_index: self.row,
+            rows_len: self.rows_len,
+        }
+    }
+}
+
+/// Iterator over the stored and computed values of a row
+///
+/// Returned by [`ComputedRow::into_iter`]
+pub struct ComputedRowIter<'t, K, V> {
+    stored_iter: BorrowedRowIter<'t, K, V>,
+    computed_iter: <&'t HashMap<K, ComputedColumn<K, V>> as IntoIterator>::IntoIter,
+    row: HashTableRowBorrowed<'t, K, V>,
+    row_index: usize,
+    rows_len: usize,
+}
+
+impl<'t, K, V> Iterator for ComputedRowIter<'t, K, V>
+where
+    K: Hash + Eq,
+{
+    type Item = (&'t K, ValueRef<'t, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((k, v)) = self.stored_iter.next() {
+            return Some((k, ValueRef::Borrowed(v)));
+        }
+        let indices_table = self.row.indices_table;
+        let (k, column) = self
+            .computed_iter
+            .by_ref()
+            .find(|(k, _)| !indices_table.contains_key(*k))?;
+        Some((k, column.value(self.row, self.row_index, self.rows_len)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<K: Hash + Eq, V> FusedIterator for ComputedRowIter<'_, K, V> {}
+
+impl<K: Hash + Eq, V> ExactSizeIterator for ComputedRowIter<'_, K, V> {
+    fn len(&self) -> usize {
+        let computed_keys = self.computed_iter.clone().map(|(k, _)| k);
+        self.stored_iter.len() + visible_len(self.row.indices_table, computed_keys)
+    }
+}
+
+/// Row-wise iterator that borrows a [`ComputedHashTable`]
+///
+/// Returned by [`ComputedHashTable::iter`]
+pub struct ComputedHashTableIter<'t, K, V> {
+    row: usize,
+    table: &'t ComputedHashTable<K, V>,
+}
+
+impl<K, V> Clone for ComputedHashTableIter<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for ComputedHashTableIter<'_, K, V> {}
+
+impl<'t, K, V> Iterator for ComputedHashTableIter<'t, K, V> {
+    type Item = ComputedRow<'t, K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let val = self.table.get_row(self.row)?;
+        self.row += 1;
+        Some(val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<K, V> FusedIterator for ComputedHashTableIter<'_, K, V> {}
+
+impl<K, V> ExactSizeIterator for ComputedHashTableIter<'_, K, V> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.table.rows_len() - self.row
+    }
+}
+
+impl<'t, K, V> IntoIterator for &'t ComputedHashTable<K, V> {
+    type Item = ComputedRow<'t, K, V>;
+    type IntoIter = ComputedHashTableIter<'t, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
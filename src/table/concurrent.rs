@@ -0,0 +1,299 @@
+//! Tables shared between threads
+
+use std::{
+    borrow::Borrow,
+    sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::{
+    row::{borrowed::HashTableRowBorrowed, mutable::HashTableMutableBorrowedRow},
+    typedefs::*,
+    HashTable,
+};
+
+/// Number of rows guarded by one lock
+pub const ROWS_PER_CHUNK: usize = 64;
+
+/// A table that can be read and written from many threads at once.
+///
+/// The set of columns is fixed when the table is created. Rows are stored in chunks of
+/// [`ROWS_PER_CHUNK`] rows with a lock per chunk, so threads updating different parts of the
+/// table and threads appending rows rarely wait for each other. Rows are never removed, so a row
+/// index stays valid for the lifetime of the table.
+///
+/// Closures passed to [`Self::with_row`], [`Self::with_row_mut`] and [`Self::update`] run while
+/// the chunk of the row is locked and must not access the same table, which may deadlock.
+///
+/// Poisoned locks are ignored: a panic in a closure passed to [`Self::with_row_mut`] may leave
+/// the row partially updated, but the table stays usable.
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::table::concurrent::ConcurrentHashTable;
+/// let table = ConcurrentHashTable::with_columns(["thread", "item"]);
+/// std::thread::scope(|s| {
+///     for thread in 0..4 {
+///         let table = &table;
+///         s.spawn(move || {
+///             for item in 0..100 {
+///                 table.push_row([("thread", thread), ("item", item)]);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(table.rows_len(), 400);
+///
+/// table.update("item", 0, |item| *item += 1000);
+/// let snapshot = table.snapshot();
+/// assert!(*snapshot.get("item", 0).unwrap() >= 1000);
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentHashTable<K, V> {
+    indices_table: HashMap<K, usize>,
+    chunks: RwLock<Vec<RwLock<Vec<V>>>>,
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<K, V> ConcurrentHashTable<K, V> {
+    /// Returns the number of columns in this table.
+    pub fn columns_len(&self) -> usize {
+        self.indices_table.len()
+    }
+
+    /// Returns the number of rows in this table.
+    pub fn rows_len(&self) -> usize {
+        let chunks = read(&self.chunks);
+        match chunks.last() {
+            None => 0,
+            Some(last) => {
+                let last_rows = read(last)
+                    .len()
+                    .checked_div(self.columns_len())
+                    .unwrap_or(0);
+                (chunks.len() - 1) * ROWS_PER_CHUNK + last_rows
+            }
+        }
+    }
+
+    /// Return an iterator over the column keys.
+    pub fn column_keys(&self) -> Keys<'_, K, usize> {
+        self.indices_table.keys()
+    }
+
+    fn chunk_len(&self) -> usize {
+        ROWS_PER_CHUNK * self.columns_len()
+    }
+
+    /// Append the values of a row, returning its index.
+    fn push_values(&self, values: Vec<V>) -> usize {
+        if self.columns_len() == 0 {
+            return 0;
+        }
+        {
+            let chunks = read(&self.chunks);
+            if let Some(last) = chunks.last() {
+                let mut last = write(last);
+                if last.len() < self.chunk_len() {
+                    let row = (chunks.len() - 1) * ROWS_PER_CHUNK + last.len() / self.columns_len();
+                    last.extend(values);
+                    return row;
+                }
+            }
+        }
+        let mut chunks = write(&self.chunks);
+        // Another thread may have added a chunk while the lock was released
+        let full_chunks = chunks.len().saturating_sub(1);
+        if let Some(last) = chunks.last_mut() {
+            let last = last.get_mut().unwrap_or_else(PoisonError::into_inner);
+            if last.len() < self.chunk_len() {
+                let row = full_chunks * ROWS_PER_CHUNK + last.len() / self.columns_len();
+                last.extend(values);
+                return row;
+            }
+        }
+        let row = chunks.len() * ROWS_PER_CHUNK;
+        let mut chunk = Vec::with_capacity(self.chunk_len());
+        chunk.extend(values);
+        chunks.push(RwLock::new(chunk));
+        row
+    }
+
+    /// Call `f` with a borrowed row while holding the lock of its chunk.
+    ///
+    /// Returns `None` if `row` is bigger than or equal to the number of rows.
+    pub fn with_row<R, F>(&self, row: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(HashTableRowBorrowed<'_, K, V>) -> R,
+    {
+        let chunks = read(&self.chunks);
+        let chunk = read(chunks.get(row / ROWS_PER_CHUNK)?);
+        let start = (row % ROWS_PER_CHUNK) * self.columns_len();
+        let row_values = chunk.get(start..start + self.columns_len())?;
+        Some(f(HashTableRowBorrowed {
+            indices_table: &self.indices_table,
+            row_values,
+        }))
+    }
+
+    /// Call `f` with a mutable row while holding the lock of its chunk.
+    ///
+    /// Returns `None` if `row` is bigger than or equal to the number of rows.
+    pub fn with_row_mut<R, F>(&self, row: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(HashTableMutableBorrowedRow<'_, K, V>) -> R,
+    {
+        let chunks = read(&self.chunks);
+        let mut chunk = write(chunks.get(row / ROWS_PER_CHUNK)?);
+        let start = (row % ROWS_PER_CHUNK) * self.columns_len();
+        let values = chunk.get_mut(start..start + self.columns_len())?;
+        Some(f(HashTableMutableBorrowedRow {
+            indices_table: &self.indices_table,
+            values,
+        }))
+    }
+
+    /// Take the values out of the concurrent table.
+    pub fn into_table(self) -> HashTable<K, V> {
+        let chunks = self
+            .chunks
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut values_vector = Vec::with_capacity(chunks.len() * ROWS_PER_CHUNK);
+        for chunk in chunks {
+            values_vector.extend(chunk.into_inner().unwrap_or_else(PoisonError::into_inner));
+        }
+        HashTable {
+            indices_table: self.indices_table,
+            values_vector,
+        }
+    }
+}
+
+impl<K, V> ConcurrentHashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Create a table with the provided columns and no rows.
+    pub fn with_columns(columns: impl IntoIterator<Item = K>) -> Self {
+        HashTable::with_columns(columns).into()
+    }
+
+    /// Add a row to the table and return its index.
+    ///
+    /// Rows pushed by different threads at the same time are added in an unspecified order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row doesn't have exactly one value for every column.
+    pub fn push_row<I>(&self, row: I) -> usize
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut values: Vec<Option<V>> = (0..self.columns_len()).map(|_| None).collect();
+        for (key, value) in row {
+            let index = *self
+                .indices_table
+                .get(&key)
+                .expect("Row must only have values for the columns of the table");
+            assert!(
+                values[index].replace(value).is_none(),
+                "Row must have one value for each column"
+            );
+        }
+        let values = values
+            .into_iter()
+            .map(|v| v.expect("Row must have one value for each column"))
+            .collect();
+        self.push_values(values)
+    }
+
+    /// Add a row to the table using a generator function that returns the value from the column
+    /// key, and return its index.
+    pub fn push_row_with<F>(&self, mut row_generator: F) -> usize
+    where
+        F: FnMut(&K) -> V,
+    {
+        let mut keys = self.indices_table.iter().collect::<Vec<_>>();
+        keys.sort_by_key(|(_, i)| *i);
+        let values = keys.into_iter().map(|(k, _)| row_generator(k)).collect();
+        self.push_values(values)
+    }
+
+    /// Get a copy of the value in the `column` of the `row`.
+    pub fn get_cloned<Q>(&self, column: &Q, row: usize) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.with_row(row, |row| row.get(column).cloned())?
+    }
+
+    /// Call `f` with a mutable reference to the value in the `column` of the `row`.
+    ///
+    /// Returns `None` if there is no such column or row.
+    pub fn update<Q, R, F>(&self, column: &Q, row: usize, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&mut V) -> R,
+    {
+        let index = *self.indices_table.get(column)?;
+        self.with_row_mut(row, |row| f(&mut row.values[index]))
+    }
+
+    /// Copy the table as it is at one moment.
+    ///
+    /// All chunks are locked for reading while they are copied, so the snapshot never contains
+    /// partially applied updates of [`Self::with_row_mut`] or [`Self::update`].
+    pub fn snapshot(&self) -> HashTable<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let chunks = read(&self.chunks);
+        let guards: Vec<_> = chunks.iter().map(read).collect();
+        let mut values_vector = Vec::with_capacity(guards.iter().map(|g| g.len()).sum());
+        for guard in &guards {
+            values_vector.extend_from_slice(guard);
+        }
+        HashTable {
+            indices_table: self.indices_table.clone(),
+            values_vector,
+        }
+    }
+}
+
+impl<K, V> From<HashTable<K, V>> for ConcurrentHashTable<K, V> {
+    fn from(table: HashTable<K, V>) -> Self {
+        let HashTable {
+            indices_table,
+            values_vector,
+        } = table;
+        let chunk_len = (ROWS_PER_CHUNK * indices_table.len()).max(1);
+        let mut chunks = Vec::with_capacity(values_vector.len().div_ceil(chunk_len));
+        let mut values = values_vector.into_iter().peekable();
+        while values.peek().is_some() {
+            let mut chunk = Vec::with_capacity(chunk_len);
+            chunk.extend(values.by_ref().take(chunk_len));
+            chunks.push(RwLock::new(chunk));
+        }
+        Self {
+            indices_table,
+            chunks: RwLock::new(chunks),
+        }
+    }
+}
+
+impl<K, V> Default for ConcurrentHashTable<K, V> {
+    fn default() -> Self {
+        HashTable::default().into()
+    }
+}
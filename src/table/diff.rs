@@ -0,0 +1,383 @@
+//! Differences between tables
+
+use std::{borrow::Borrow, fmt};
+
+use crate::{typedefs::*, HashTable};
+
+/// Error of computing or applying a [`TableDiff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffError {
+    /// The key column with this position in the list of key columns is missing from a table
+    MissingKeyColumn(usize),
+    /// The row with this index has the same key as an earlier row
+    DuplicateKey(usize),
+    /// The removed row with this position in [`TableDiff::removed_rows`] is not in the table
+    MissingRemovedRow(usize),
+    /// The changed row with this position in [`TableDiff::changed_rows`] is not in the table
+    MissingChangedRow(usize),
+    /// A value of the row with this position in [`TableDiff::changed_rows`] is not the old value
+    /// recorded in the diff
+    Conflict(usize),
+    /// The columns of the table don't match the columns the diff was computed from
+    ColumnMismatch,
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingKeyColumn(i) => write!(f, "key column {i} is missing from the table"),
+            Self::DuplicateKey(row) => write!(f, "row {row} has a duplicate key"),
+            Self::MissingRemovedRow(i) => write!(f, "removed row {i} is not in the table"),
+            Self::MissingChangedRow(i) => write!(f, "changed row {i} is not in the table"),
+            Self::Conflict(i) => write!(f, "changed row {i} doesn't have the expected old values"),
+            Self::ColumnMismatch => write!(f, "columns of the table don't match the diff"),
+        }
+    }
+}
+
+impl std::error::Error for DiffError {}
+
+/// Change of one value of a row
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellChange<K, V> {
+    pub column: K,
+    /// Value before the change, `None` if the column is added by the diff
+    pub old: Option<V>,
+    pub new: V,
+}
+
+/// Changes of a row that is present in both tables
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RowChange<K, V> {
+    /// Values of the key columns of the row, in the order of [`TableDiff::key_columns`]
+    pub key: Vec<V>,
+    pub cells: Vec<CellChange<K, V>>,
+}
+
+/// Difference between two tables whose rows are identified by the values of key columns.
+///
+/// Computed by [`HashTable::diff`] and applied by [`HashTable::apply`].
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::HashTable;
+/// let yesterday = HashTable::from_column_keys_and_rows(
+///     ["code", "rate"],
+///     [["EUR", "1.08"], ["GBP", "1.27"], ["SEK", "0.095"]],
+/// );
+/// let today = HashTable::from_column_keys_and_rows(
+///     ["code", "rate"],
+///     [["EUR", "1.09"], ["GBP", "1.27"], ["NOK", "0.093"]],
+/// );
+/// let diff = yesterday.diff(&today, [&"code"]).unwrap();
+/// assert_eq!(diff.removed_rows, [vec!["SEK"]]);
+/// assert_eq!(diff.added_rows, [vec!["NOK", "0.093"]]);
+/// assert_eq!(diff.changed_rows[0].cells[0].new, "1.09");
+///
+/// let mut patched = yesterday.clone();
+/// patched.apply(&diff).unwrap();
+/// assert_eq!(patched.get(&"rate", 0), Some(&"1.09"));
+/// assert_eq!(patched.get(&"code", 2), Some(&"NOK"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableDiff<K, V> {
+    /// Columns whose values identify a row
+    pub key_columns: Vec<K>,
+    /// Columns of the new table in column-index order
+    pub columns: Vec<K>,
+    /// Columns of the new table that are not in the old table, in column-index order
+    pub added_columns: Vec<K>,
+    /// Columns of the old table that are not in the new table, in column-index order
+    pub removed_columns: Vec<K>,
+    /// Rows of the new table that are not in the old table, with values in the order of
+    /// [`Self::columns`]
+    pub added_rows: Vec<Vec<V>>,
+    /// Keys of the rows of the old table that are not in the new table
+    pub removed_rows: Vec<Vec<V>>,
+    /// Rows present in both tables that have different values or get values of added columns
+    pub changed_rows: Vec<RowChange<K, V>>,
+}
+
+impl<K, V> TableDiff<K, V> {
+    /// Returns `true` if the tables are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.added_rows.is_empty()
+            && self.removed_rows.is_empty()
+            && self.changed_rows.is_empty()
+    }
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+{
+    /// Map from the values of the key columns to the row with these values
+    fn rows_by_key(&self, key_indices: &[usize]) -> Result<HashMap<Vec<&V>, usize>, DiffError> {
+        let mut rows = HashMap::with_capacity(self.rows_len());
+        for (row, values) in self.iter().enumerate() {
+            let key = key_indices.iter().map(|i| &values.row_values[*i]).collect();
+            if rows.insert(key, row).is_some() {
+                return Err(DiffError::DuplicateKey(row));
+            }
+        }
+        Ok(rows)
+    }
+
+    fn key_indices<'k>(
+        &self,
+        key_columns: impl IntoIterator<Item = &'k K>,
+    ) -> Result<Vec<usize>, DiffError>
+    where
+        K: 'k,
+    {
+        key_columns
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| self.column_index(key).ok_or(DiffError::MissingKeyColumn(i)))
+            .collect()
+    }
+
+    /// Compute the changes that turn this table into the `other` table.
+    ///
+    /// Rows of the two tables are matched by the values of the `key_columns`, which must be present
+    /// in both tables and have unique values in each of them. Values of other columns are compared
+    /// if the column is present in both tables.
+    pub fn diff<'q, Q, I>(&self, other: &Self, key_columns: I) -> Result<TableDiff<K, V>, DiffError>
+    where
+        I: IntoIterator<Item = &'q Q>,
+        K: Borrow<Q> + Clone,
+        Q: Hash + Eq + ?Sized + 'q,
+        V: Clone,
+    {
+        let key_columns: Vec<&K> = key_columns
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| {
+                self.indices_table
+                    .get_key_value(key)
+                    .map(|(k, _)| k)
+                    .ok_or(DiffError::MissingKeyColumn(i))
+            })
+            .collect::<Result<_, _>>()?;
+        let self_keys = self.key_indices(key_columns.iter().copied())?;
+        let other_keys = other.key_indices(key_columns.iter().copied())?;
+        let self_rows = self.rows_by_key(&self_keys)?;
+        other.rows_by_key(&other_keys)?;
+
+        let columns = other.column_keys_in_order();
+        let added_columns: Vec<&K> = columns
+            .iter()
+            .copied()
+            .filter(|k| !self.indices_table.contains_key::<K>(*k))
+            .collect();
+        let removed_columns = self
+            .column_keys_in_order()
+            .into_iter()
+            .filter(|k| !other.indices_table.contains_key::<K>(*k))
+            .cloned()
+            .collect();
+        // Columns present in both tables with their indices in this and the other table
+        let common_columns: Vec<(&K, usize, usize)> = columns
+            .iter()
+            .filter_map(|k| {
+                Some((
+                    *k,
+                    self.column_index::<K>(*k)?,
+                    other.column_index::<K>(*k)?,
+                ))
+            })
+            .filter(|(_, i, _)| !self_keys.contains(i))
+            .collect();
+
+        let mut added_rows = Vec::new();
+        let mut changed_rows = Vec::new();
+        let mut matched = vec![false; self.rows_len()];
+        for other_row in other.iter() {
+            let key: Vec<&V> = other_keys
+                .iter()
+                .map(|i| &other_row.row_values[*i])
+                .collect();
+            let Some(&row) = self_rows.get(&key) else {
+                added_rows.push(other_row.row_values.to_vec());
+                continue;
+            };
+            matched[row] = true;
+            let self_row = self.get_row(row).expect("Rows of the key map exist");
+            let mut cells: Vec<CellChange<K, V>> = common_columns
+                .iter()
+                .filter(|(_, i, j)| self_row.row_values[*i] != other_row.row_values[*j])
+                .map(|(k, i, j)| CellChange {
+                    column: (*k).clone(),
+                    old: Some(self_row.row_values[*i].clone()),
+                    new: other_row.row_values[*j].clone(),
+                })
+                .collect();
+            cells.extend(added_columns.iter().map(|k| {
+                CellChange {
+                    column: (*k).clone(),
+                    old: None,
+                    new: other_row
+                        .get::<K>(*k)
+                        .expect("Added columns are in the other table")
+                        .clone(),
+                }
+            }));
+            if !cells.is_empty() {
+                changed_rows.push(RowChange {
+                    key: key.into_iter().cloned().collect(),
+                    cells,
+                });
+            }
+        }
+        let removed_rows = self
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(row, _)| {
+                self_keys
+                    .iter()
+                    .map(|i| row.row_values[*i].clone())
+                    .collect()
+            })
+            .collect();
+
+        Ok(TableDiff {
+            key_columns: key_columns.into_iter().cloned().collect(),
+            columns: columns.into_iter().cloned().collect(),
+            added_columns: added_columns.into_iter().cloned().collect(),
+            removed_columns,
+            added_rows,
+            removed_rows,
+            changed_rows,
+        })
+    }
+
+    /// Apply the changes of a diff computed by [`Self::diff`] from a table with the same contents.
+    ///
+    /// The diff is checked against the table before anything is changed, so the table is left
+    /// as is if an error is returned. Added columns are placed after the existing columns.
+    pub fn apply(&mut self, diff: &TableDiff<K, V>) -> Result<(), DiffError>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let key_indices = self.key_indices(&diff.key_columns)?;
+        // Diffs may be deserialized from untrusted input, so the column lists are checked before
+        // counting on them
+        if has_duplicates(&diff.removed_columns)
+            || has_duplicates(&diff.added_columns)
+            || has_duplicates(&diff.columns)
+            || diff
+                .removed_columns
+                .iter()
+                .any(|k| !self.indices_table.contains_key(k))
+            || diff
+                .added_columns
+                .iter()
+                .any(|k| self.indices_table.contains_key(k))
+            || self.columns_len() - diff.removed_columns.len() + diff.added_columns.len()
+                != diff.columns.len()
+        {
+            return Err(DiffError::ColumnMismatch);
+        }
+        let is_new_column = |k: &K| {
+            (self.indices_table.contains_key(k) && !diff.removed_columns.contains(k))
+                || diff.added_columns.contains(k)
+        };
+        if !diff.columns.iter().all(is_new_column)
+            || diff
+                .added_rows
+                .iter()
+                .any(|values| values.len() != diff.columns.len())
+        {
+            return Err(DiffError::ColumnMismatch);
+        }
+
+        let (removed_rows, changed_rows) = {
+            let rows = self.rows_by_key(&key_indices)?;
+            let find = |key: &Vec<V>| rows.get(&key.iter().collect::<Vec<_>>()).copied();
+            let removed_rows = diff
+                .removed_rows
+                .iter()
+                .enumerate()
+                .map(|(i, key)| find(key).ok_or(DiffError::MissingRemovedRow(i)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let changed_rows = diff
+                .changed_rows
+                .iter()
+                .enumerate()
+                .map(|(i, change)| find(&change.key).ok_or(DiffError::MissingChangedRow(i)))
+                .collect::<Result<Vec<_>, _>>()?;
+            (removed_rows, changed_rows)
+        };
+        let mut removed = vec![false; self.rows_len()];
+        for row in removed_rows {
+            removed[row] = true;
+        }
+
+        // Cells of existing columns as (row, column index, new value), and values of added
+        // columns for every row
+        let mut updates = Vec::new();
+        let mut added_values: Vec<Vec<Option<&V>>> =
+            vec![vec![None; self.rows_len()]; diff.added_columns.len()];
+        for (i, (change, row)) in diff.changed_rows.iter().zip(changed_rows).enumerate() {
+            for cell in &change.cells {
+                match &cell.old {
+                    Some(old) => {
+                        let column = self
+                            .column_index(&cell.column)
+                            .ok_or(DiffError::ColumnMismatch)?;
+                        if &self.values_vector[self.row_start(row) + column] != old {
+                            return Err(DiffError::Conflict(i));
+                        }
+                        updates.push((row, column, &cell.new));
+                    }
+                    None => {
+                        let column = diff
+                            .added_columns
+                            .iter()
+                            .position(|k| *k == cell.column)
+                            .ok_or(DiffError::ColumnMismatch)?;
+                        added_values[column][row] = Some(&cell.new);
+                    }
+                }
+            }
+        }
+        let added_values: Vec<Vec<V>> = added_values
+            .into_iter()
+            .map(|values| {
+                values
+                    .into_iter()
+                    .zip(&removed)
+                    .filter(|(_, removed)| !**removed)
+                    .map(|(value, _)| value.cloned().ok_or(DiffError::ColumnMismatch))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        for (row, column, value) in updates {
+            let index = self.row_start(row) + column;
+            self.values_vector[index] = value.clone();
+        }
+        let mut removed = removed.into_iter();
+        self.retain(|_| !removed.next().unwrap_or(false));
+        self.remove_columns(&diff.removed_columns);
+        self.insert_columns(diff.added_columns.iter().cloned().zip(added_values));
+        for values in &diff.added_rows {
+            self.push_row(diff.columns.iter().cloned().zip(values.iter().cloned()));
+        }
+        Ok(())
+    }
+}
+
+/// Check whether a key appears more than once
+fn has_duplicates<K: Hash + Eq>(keys: &[K]) -> bool {
+    let mut seen = HashSet::with_capacity(keys.len());
+    !keys.iter().all(|key| seen.insert(key))
+}
@@ -0,0 +1,333 @@
+//! Entry APIs for cells and for rows identified by the value of a key column
+
+use std::{borrow::Borrow, cmp::Ordering};
+
+use crate::{
+    row::{
+        borrowed::HashTableRowBorrowed, mutable::HashTableMutableBorrowedRow,
+        value_owned::HashTableRowValueOwned,
+    },
+    typedefs::*,
+    HashTable,
+};
+
+/// A row of a table identified by the value of its key column, which may or may not be present.
+///
+/// Returned by [`HashTable::row_entry`].
+#[derive(Debug)]
+pub enum RowEntry<'t, K, V> {
+    /// A row with the key is present in the table
+    Occupied(OccupiedRowEntry<'t, K, V>),
+    /// No row has the key
+    Vacant(VacantRowEntry<'t, K, V>),
+}
+
+/// A row that is present in the table.
+#[derive(Debug)]
+pub struct OccupiedRowEntry<'t, K, V> {
+    table: &'t mut HashTable<K, V>,
+    row: usize,
+}
+
+/// A row that is not present in the table.
+#[derive(Debug)]
+pub struct VacantRowEntry<'t, K, V> {
+    table: &'t mut HashTable<K, V>,
+    column: usize,
+    key: V,
+}
+
+/// A cell of a table identified by its column and row, in a row that may not be present yet.
+///
+/// Returned by [`HashTable::entry`].
+#[derive(Debug)]
+pub enum CellEntry<'t, K, V> {
+    /// The row is present in the table
+    Occupied(OccupiedCellEntry<'t, V>),
+    /// The row is the next row to be added to the table
+    Vacant(VacantCellEntry<'t, K, V>),
+}
+
+/// A cell in a row that is present in the table.
+#[derive(Debug)]
+pub struct OccupiedCellEntry<'t, V> {
+    value: &'t mut V,
+}
+
+/// A cell in the row that would be added next to the table.
+#[derive(Debug)]
+pub struct VacantCellEntry<'t, K, V> {
+    table: &'t mut HashTable<K, V>,
+    column: usize,
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Get the cell in the `column` and `row` for in-place changes, or for adding the row if
+    /// `row` is the number of rows.
+    ///
+    /// Returns `None` if there is no such column or `row` is bigger than the number of rows.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let mut totals: HashTable<&str, i32> = HashTable::with_columns(["sum", "count"]);
+    /// for value in [3, 4] {
+    ///     *totals.entry(&"sum", 0).unwrap().or_default() += value;
+    ///     *totals.entry(&"count", 0).unwrap().or_default() += 1;
+    /// }
+    /// assert_eq!(totals.rows_len(), 1);
+    /// assert_eq!(totals.get(&"sum", 0), Some(&7));
+    /// assert_eq!(totals.get(&"count", 0), Some(&2));
+    /// assert!(totals.entry(&"sum", 2).is_none());
+    /// ```
+    pub fn entry<Q>(&mut self, column: &Q, row: usize) -> Option<CellEntry<'_, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let column = self.column_index(column)?;
+        match row.cmp(&self.rows_len()) {
+            Ordering::Less => {
+                let index = self.row_start(row) + column;
+                Some(CellEntry::Occupied(OccupiedCellEntry {
+                    value: &mut self.values_vector[index],
+                }))
+            }
+            Ordering::Equal => Some(CellEntry::Vacant(VacantCellEntry {
+                table: self,
+                column,
+            })),
+            Ordering::Greater => None,
+        }
+    }
+
+    /// Get the first row whose value in the key `column` is equal to the `key`, for in-place
+    /// changes or insertion.
+    ///
+    /// The table is scanned once. Returns `None` if there is no such column.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let mut counts: HashTable<&str, String> = HashTable::with_columns(["word", "count"]);
+    /// for word in ["a", "b", "a"] {
+    ///     counts
+    ///         .row_entry(&"word", word.to_owned())
+    ///         .unwrap()
+    ///         .and_modify(|mut row| {
+    ///             let count = row.get(&"count").unwrap();
+    ///             *count = (count.parse::<u32>().unwrap() + 1).to_string();
+    ///         })
+    ///         .or_insert_with(|_| "1".to_owned());
+    /// }
+    /// assert_eq!(counts.rows_len(), 2);
+    /// assert_eq!(counts.get(&"count", 0).unwrap(), "2");
+    /// ```
+    pub fn row_entry<Q>(&mut self, column: &Q, key: V) -> Option<RowEntry<'_, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: PartialEq,
+    {
+        let column = self.column_index(column)?;
+        let row = self
+            .values_vector
+            .chunks_exact(self.columns_len())
+            .position(|values| values[column] == key);
+        Some(match row {
+            Some(row) => RowEntry::Occupied(OccupiedRowEntry { table: self, row }),
+            None => RowEntry::Vacant(VacantRowEntry {
+                table: self,
+                column,
+                key,
+            }),
+        })
+    }
+}
+
+impl<'t, K, V> RowEntry<'t, K, V> {
+    /// Returns the index of the row, if it's present.
+    pub fn row(&self) -> Option<usize> {
+        match self {
+            Self::Occupied(entry) => Some(entry.row()),
+            Self::Vacant(_) => None,
+        }
+    }
+
+    /// Change the row if it's present.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(HashTableMutableBorrowedRow<'_, K, V>),
+    {
+        if let Self::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    /// Get the row, adding it if it's not present.
+    ///
+    /// See [`VacantRowEntry::insert_with`].
+    pub fn or_insert_with<F>(self, row_generator: F) -> HashTableMutableBorrowedRow<'t, K, V>
+    where
+        F: FnMut(&K) -> V,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert_with(row_generator),
+        }
+    }
+}
+
+impl<'t, K, V> OccupiedRowEntry<'t, K, V> {
+    /// Returns the index of the row.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Get the row.
+    pub fn get(&self) -> HashTableRowBorrowed<'_, K, V> {
+        self.table
+            .get_row(self.row)
+            .expect("Entry row is in the table")
+    }
+
+    /// Get the row with mutable access.
+    pub fn get_mut(&mut self) -> HashTableMutableBorrowedRow<'_, K, V> {
+        self.table
+            .get_row_mut(self.row)
+            .expect("Entry row is in the table")
+    }
+
+    /// Convert the entry into the row with mutable access that borrows the table.
+    pub fn into_mut(self) -> HashTableMutableBorrowedRow<'t, K, V> {
+        self.table
+            .get_row_mut(self.row)
+            .expect("Entry row is in the table")
+    }
+
+    /// Remove the row from the table and take ownership of its values.
+    pub fn remove(self) -> HashTableRowValueOwned<'t, K, V> {
+        self.table
+            .remove_row(self.row)
+            .expect("Entry row is in the table")
+    }
+}
+
+impl<'t, K, V> VacantRowEntry<'t, K, V> {
+    /// Get the key the entry was looked up with.
+    pub fn key(&self) -> &V {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> V {
+        self.key
+    }
+
+    /// Add a row with the key in the key column and values generated from the column keys in
+    /// the other columns.
+    ///
+    /// The `row_generator` is not called for the key column.
+    pub fn insert_with<F>(self, mut row_generator: F) -> HashTableMutableBorrowedRow<'t, K, V>
+    where
+        F: FnMut(&K) -> V,
+    {
+        let Self { table, column, key } = self;
+        let row = table.rows_len();
+        let mut keys: Vec<(&K, &usize)> = table.indices_table.iter().collect();
+        keys.sort_by_key(|(_, i)| **i);
+        let mut key = Some(key);
+        let values: Vec<V> = keys
+            .into_iter()
+            .map(|(k, i)| {
+                if *i == column {
+                    key.take().expect("Key column is unique")
+                } else {
+                    row_generator(k)
+                }
+            })
+            .collect();
+        table.values_vector.extend(values);
+        table.get_row_mut(row).expect("Row was just added")
+    }
+}
+
+impl<'t, K, V> CellEntry<'t, K, V>
+where
+    K: Hash + Eq,
+{
+    /// Change the value if the row is present.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Self::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    /// Get the value, adding the row if it's not present.
+    ///
+    /// See [`VacantCellEntry::insert_with`].
+    pub fn or_insert_with<F>(self, row_generator: F) -> &'t mut V
+    where
+        F: FnMut(&K) -> V,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert_with(row_generator),
+        }
+    }
+
+    /// Get the value, adding a row of default values if it's not present.
+    pub fn or_default(self) -> &'t mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(|_| V::default())
+    }
+}
+
+impl<'t, V> OccupiedCellEntry<'t, V> {
+    /// Get the value.
+    pub fn get(&self) -> &V {
+        self.value
+    }
+
+    /// Get the value with mutable access.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.value
+    }
+
+    /// Convert the entry into the value with mutable access that borrows the table.
+    pub fn into_mut(self) -> &'t mut V {
+        self.value
+    }
+
+    /// Replace the value, returning the previous value.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.value, value)
+    }
+}
+
+impl<'t, K, V> VacantCellEntry<'t, K, V>
+where
+    K: Hash + Eq,
+{
+    /// Add a row with values generated from the column keys, and get the value of the entry's
+    /// column.
+    pub fn insert_with<F>(self, row_generator: F) -> &'t mut V
+    where
+        F: FnMut(&K) -> V,
+    {
+        let Self { table, column } = self;
+        table.push_row_with(row_generator);
+        let index = table.values_vector.len() - table.columns_len() + column;
+        &mut table.values_vector[index]
+    }
+}
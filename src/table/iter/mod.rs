@@ -73,21 +73,29 @@ impl<K, V> HashTable<K, V> {
     }
     */
 
-    /// Column-wise iterator that takes ownership of the keys and values
+    /// Column-wise iterator that takes ownership of the keys and values.
+    ///
+    /// Columns are returned in column-index order.
     pub fn into_iter_columns(self) -> HashTableOwnedIntoIterColumn<K, V> {
+        let mut indices: Vec<(K, usize)> = self.indices_table.into_iter().collect();
+        indices.sort_unstable_by_key(|(_, i)| *i);
         HashTableOwnedIntoIterColumn {
-            row_len: self.columns_len(),
-            indices_iter: self.indices_table.into_iter(),
+            row_len: indices.len(),
+            indices_iter: indices.into_iter(),
             values: self.values_vector.into_iter().map(Option::Some).collect(),
         }
     }
 
-    /// Column-wise iterator that borrows the values from the table
-    #[inline]
+    /// Column-wise iterator that borrows the values from the table.
+    ///
+    /// Columns are returned in column-index order.
     pub fn iter_columns(&self) -> HashTableBorrowedIterColumn<'_, K, V> {
+        let mut indices: Vec<(&K, usize)> =
+            self.indices_table.iter().map(|(k, i)| (k, *i)).collect();
+        indices.sort_unstable_by_key(|(_, i)| *i);
         HashTableBorrowedIterColumn {
             row_len: self.columns_len(),
-            indices_iter: self.indices_table.iter(),
+            indices_iter: indices.into_iter(),
             values: &self.values_vector,
         }
     }
@@ -166,7 +174,7 @@ impl<'t, K, V> Iterator for HashTableMutIter<'t, K, V> {
 /// Returned by [`HashTable::into_iter_columns`]
 #[derive(Debug)]
 pub struct HashTableOwnedIntoIterColumn<K, V> {
-    indices_iter: <HashMap<K, usize> as IntoIterator>::IntoIter,
+    indices_iter: std::vec::IntoIter<(K, usize)>,
     values: Vec<Option<V>>,
     row_len: usize,
 }
@@ -206,7 +214,7 @@ impl<K, V> ExactSizeIterator for HashTableOwnedIntoIterColumn<K, V> {
 /// Returned by [`HashTable::iter_columns`]
 #[derive(Debug)]
 pub struct HashTableBorrowedIterColumn<'t, K, V> {
-    indices_iter: <&'t HashMap<K, usize> as IntoIterator>::IntoIter,
+    indices_iter: std::vec::IntoIter<(&'t K, usize)>,
     values: &'t [V],
     row_len: usize,
 }
@@ -229,7 +237,7 @@ impl<'t, K, V> Iterator for HashTableBorrowedIterColumn<'t, K, V> {
         let values = self
             .values
             .chunks_exact(self.row_len)
-            .map(|chunk| &chunk[*idx])
+            .map(|chunk| &chunk[idx])
             .collect();
         Some(HashTableColumnBorrowed {
             column: key,
@@ -0,0 +1,568 @@
+//! Tables with undo and redo history
+
+use std::{
+    borrow::Borrow,
+    ops::{Index, IndexMut},
+};
+
+use crate::{row::borrowed::HashTableRowBorrowed, typedefs::*, HashTable};
+
+/// A reversible change of a table.
+///
+/// Applying an operation returns the operation that reverts it.
+#[derive(Debug)]
+enum Operation<K, V> {
+    /// Swap the value of a cell with `value`
+    Set {
+        row: usize,
+        column: usize,
+        value: V,
+    },
+    InsertRow {
+        row: usize,
+        values: Vec<V>,
+    },
+    RemoveRow {
+        row: usize,
+    },
+    InsertColumn {
+        index: usize,
+        key: K,
+        values: Vec<V>,
+    },
+    RemoveColumn {
+        index: usize,
+    },
+}
+
+impl<K, V> Operation<K, V>
+where
+    K: Hash + Eq,
+{
+    fn apply(self, table: &mut HashTable<K, V>) -> Self {
+        let columns_len = table.columns_len();
+        match self {
+            Operation::Set {
+                row,
+                column,
+                mut value,
+            } => {
+                std::mem::swap(
+                    &mut table.values_vector[row * columns_len + column],
+                    &mut value,
+                );
+                Operation::Set { row, column, value }
+            }
+            Operation::InsertRow { row, values } => {
+                let start = row * columns_len;
+                table.values_vector.splice(start..start, values);
+                Operation::RemoveRow { row }
+            }
+            Operation::RemoveRow { row } => {
+                let start = row * columns_len;
+                let values = table
+                    .values_vector
+                    .drain(start..start + columns_len)
+                    .collect();
+                Operation::InsertRow { row, values }
+            }
+            Operation::InsertColumn { index, key, values } => {
+                let rows = if columns_len == 0 {
+                    values.len()
+                } else {
+                    table.rows_len()
+                };
+                for i in table.indices_table.values_mut() {
+                    if *i >= index {
+                        *i += 1;
+                    }
+                }
+                table.indices_table.insert(key, index);
+                let mut old_values = std::mem::take(&mut table.values_vector).into_iter();
+                let mut new_values = values.into_iter();
+                let mut values_vector = Vec::with_capacity(rows * (columns_len + 1));
+                for _ in 0..rows {
+                    values_vector.extend(old_values.by_ref().take(index));
+                    values_vector.extend(new_values.next());
+                    values_vector.extend(old_values.by_ref().take(columns_len - index));
+                }
+                table.values_vector = values_vector;
+                Operation::RemoveColumn { index }
+            }
+            Operation::RemoveColumn { index } => {
+                let mut key = None;
+                for (k, i) in std::mem::take(&mut table.indices_table) {
+                    match i.cmp(&index) {
+                        std::cmp::Ordering::Less => {
+                            table.indices_table.insert(k, i);
+                        }
+                        std::cmp::Ordering::Equal => key = Some(k),
+                        std::cmp::Ordering::Greater => {
+                            table.indices_table.insert(k, i - 1);
+                        }
+                    }
+                }
+                let rows = table.values_vector.len() / columns_len;
+                let mut values = Vec::with_capacity(rows);
+                let mut values_vector = Vec::with_capacity(rows * (columns_len - 1));
+                for (i, value) in std::mem::take(&mut table.values_vector)
+                    .into_iter()
+                    .enumerate()
+                {
+                    if i % columns_len == index {
+                        values.push(value);
+                    } else {
+                        values_vector.push(value);
+                    }
+                }
+                table.values_vector = values_vector;
+                Operation::InsertColumn {
+                    index,
+                    key: key.expect("Removed column index exists"),
+                    values,
+                }
+            }
+        }
+    }
+}
+
+/// Operations that are undone and redone together
+#[derive(Debug)]
+struct Step<K, V> {
+    id: u64,
+    operations: Vec<Operation<K, V>>,
+}
+
+/// Revert the operations of an unfinished transaction, last first
+fn revert_all<K, V>(table: &mut HashTable<K, V>, operations: Vec<Operation<K, V>>)
+where
+    K: Hash + Eq,
+{
+    for operation in operations.into_iter().rev() {
+        operation.apply(table);
+    }
+}
+
+/// Ends the transaction in progress when dropped, reverting its changes unless it was finished.
+///
+/// This keeps the table and the history consistent if the closure of a transaction panics.
+struct TransactionGuard<'j, K, V>
+where
+    K: Hash + Eq,
+{
+    journal: &'j mut JournaledHashTable<K, V>,
+    /// Operations of the enclosing transaction, `None` once this transaction is finished
+    outer: Option<Option<Vec<Operation<K, V>>>>,
+}
+
+impl<K, V> TransactionGuard<'_, K, V>
+where
+    K: Hash + Eq,
+{
+    /// End the transaction and take its operations.
+    fn finish(&mut self) -> Vec<Operation<K, V>> {
+        let operations = self.journal.transaction.take().unwrap_or_default();
+        self.journal.transaction = self.outer.take().expect("Transaction is finished once");
+        operations
+    }
+}
+
+impl<K, V> Drop for TransactionGuard<'_, K, V>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        if self.outer.is_some() {
+            let operations = self.finish();
+            revert_all(&mut self.journal.table, operations);
+        }
+    }
+}
+
+/// A point in the history of a [`JournaledHashTable`] that it can be rolled back to.
+///
+/// Returned by [`JournaledHashTable::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(u64);
+
+/// A table that records its changes, allowing to undo and redo them.
+///
+/// Every change made through this wrapper is stored as a reversible operation, so undoing a change
+/// costs about as much as making it, instead of keeping a copy of the whole table. Values are
+/// cloned when they are about to be overwritten, so the methods that change a value require
+/// `V: Clone`. Removed values are moved into the history and lent back by the removing method.
+///
+/// Making a change discards the changes that were undone and could be redone.
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::{HashTable, table::journal::JournaledHashTable};
+/// let mut table = JournaledHashTable::new(HashTable::from_column_keys_and_rows(
+///     ["name", "age"],
+///     [["Ann", "31"], ["Bob", "45"]],
+/// ));
+/// let start = table.checkpoint();
+/// table[(&"age", 0)] = "32";
+/// table.remove_row(1);
+/// assert_eq!(table.table().rows_len(), 1);
+///
+/// table.undo();
+/// assert_eq!(table.table().get("name", 1), Some(&"Bob"));
+/// table.redo();
+/// assert_eq!(table.table().rows_len(), 1);
+///
+/// assert!(table.rollback_to(start));
+/// assert_eq!(table.table().get("age", 0), Some(&"31"));
+/// assert_eq!(table.table().rows_len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct JournaledHashTable<K, V> {
+    table: HashTable<K, V>,
+    undo: Vec<Step<K, V>>,
+    redo: Vec<Step<K, V>>,
+    /// Operations of the transaction in progress
+    transaction: Option<Vec<Operation<K, V>>>,
+    next_id: u64,
+}
+
+impl<K, V> Default for JournaledHashTable<K, V> {
+    fn default() -> Self {
+        Self::new(HashTable::default())
+    }
+}
+
+impl<K, V> From<HashTable<K, V>> for JournaledHashTable<K, V> {
+    fn from(table: HashTable<K, V>) -> Self {
+        Self::new(table)
+    }
+}
+
+impl<K, V> JournaledHashTable<K, V> {
+    /// Start recording the changes of the `table`, with empty history.
+    pub fn new(table: HashTable<K, V>) -> Self {
+        Self {
+            table,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            transaction: None,
+            next_id: 1,
+        }
+    }
+
+    /// Get the underlying table.
+    pub fn table(&self) -> &HashTable<K, V> {
+        &self.table
+    }
+
+    /// Take the underlying table, dropping the history.
+    pub fn into_table(self) -> HashTable<K, V> {
+        self.table
+    }
+
+    /// Returns `true` if there is a change that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is an undone change that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forget all recorded changes.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Get the current point of the history to use with [`Self::rollback_to`].
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.undo.last().map_or(0, |step| step.id))
+    }
+}
+
+impl<K, V> JournaledHashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    fn record(&mut self, operation: Operation<K, V>) {
+        match &mut self.transaction {
+            Some(operations) => operations.push(operation),
+            None => {
+                self.redo.clear();
+                let id = self.next_id;
+                self.next_id += 1;
+                self.undo.push(Step {
+                    id,
+                    operations: vec![operation],
+                });
+            }
+        }
+    }
+
+    /// Apply the `operation` to the table and record the operation that reverts it.
+    fn apply(&mut self, operation: Operation<K, V>) {
+        let inverse = operation.apply(&mut self.table);
+        self.record(inverse);
+    }
+
+    /// The operation that was recorded last
+    fn last_recorded(&self) -> &Operation<K, V> {
+        match &self.transaction {
+            Some(operations) => operations.last(),
+            None => self.undo.last().and_then(|step| step.operations.last()),
+        }
+        .expect("An operation was just recorded")
+    }
+
+    /// Apply the operations of a step in reverse order, returning the step that reverts it.
+    fn revert(&mut self, step: Step<K, V>) -> Step<K, V> {
+        let operations = step
+            .operations
+            .into_iter()
+            .rev()
+            .map(|operation| operation.apply(&mut self.table))
+            .collect();
+        Step {
+            id: step.id,
+            operations,
+        }
+    }
+
+    /// Revert the last change, or the last transaction.
+    ///
+    /// Returns `false` if there is nothing to undo or a transaction is in progress.
+    pub fn undo(&mut self) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        let Some(step) = self.undo.pop() else {
+            return false;
+        };
+        let step = self.revert(step);
+        self.redo.push(step);
+        true
+    }
+
+    /// Apply the last undone change again.
+    ///
+    /// Returns `false` if there is nothing to redo or a transaction is in progress.
+    pub fn redo(&mut self) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+        let step = self.revert(step);
+        self.undo.push(step);
+        true
+    }
+
+    /// Undo all changes made after the `checkpoint`.
+    ///
+    /// The undone changes can be redone. Returns `false` without changing the table if the
+    /// checkpoint is no longer part of the history, because changes made before it were undone or
+    /// the history was cleared, or if a transaction is in progress.
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) -> bool {
+        if self.transaction.is_some()
+            || (checkpoint.0 != 0 && !self.undo.iter().any(|step| step.id == checkpoint.0))
+        {
+            return false;
+        }
+        while self.checkpoint() != checkpoint && self.undo() {}
+        true
+    }
+
+    /// Run `f` as a single change: if it returns `Ok`, its changes are undone and redone together,
+    /// if it returns `Err`, its changes are reverted.
+    ///
+    /// Transactions can be nested, an inner transaction becomes part of the outer one.
+    ///
+    /// If `f` panics, its changes are reverted as if it returned `Err` while the panic unwinds.
+    pub fn transaction<R, E, F>(&mut self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&mut Self) -> Result<R, E>,
+    {
+        let outer = self.transaction.replace(Vec::new());
+        let mut guard = TransactionGuard {
+            journal: self,
+            outer: Some(outer),
+        };
+        let result = f(guard.journal);
+        let operations = guard.finish();
+        drop(guard);
+        match result {
+            Ok(_) if operations.is_empty() => {}
+            Ok(_) => match &mut self.transaction {
+                Some(outer) => outer.extend(operations),
+                None => {
+                    self.redo.clear();
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    self.undo.push(Step { id, operations });
+                }
+            },
+            Err(_) => revert_all(&mut self.table, operations),
+        }
+        result
+    }
+
+    /// Get a reference to a value in the table.
+    pub fn get<Q>(&self, column: &Q, row: usize) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.table.get(column, row)
+    }
+
+    /// Get a mutable reference to a value in the table.
+    ///
+    /// The current value is recorded, so the change can be undone whether or not the value is
+    /// actually changed.
+    pub fn get_mut<Q>(&mut self, column: &Q, row: usize) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        let column = self.table.column_index(column)?;
+        let value = self.table.get_row(row)?.row_values[column].clone();
+        self.record(Operation::Set { row, column, value });
+        let index = self.table.row_start(row) + column;
+        Some(&mut self.table.values_vector[index])
+    }
+
+    /// Replace a value in the table, returning the previous value.
+    ///
+    /// Returns `None` and doesn't change the table if there is no such column or row.
+    pub fn set<Q>(&mut self, column: &Q, row: usize, value: V) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        let column = self.table.column_index(column)?;
+        if row >= self.table.rows_len() {
+            return None;
+        }
+        self.apply(Operation::Set { row, column, value });
+        match self.last_recorded() {
+            Operation::Set { value, .. } => Some(value.clone()),
+            _ => unreachable!("Setting a value is reverted by setting it back"),
+        }
+    }
+
+    /// Add a row to the table from an iterator of key-value pairs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row doesn't have exactly one value for every column of the table. The table
+    /// is not changed in that case.
+    pub fn push_row<I>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut values: Vec<Option<V>> = (0..self.table.columns_len()).map(|_| None).collect();
+        for (key, value) in row {
+            let Some(&index) = self.table.indices_table.get(&key) else {
+                panic!("Row contains key that is not present in the table");
+            };
+            if values[index].replace(value).is_some() {
+                panic!("Row contains the same key more than once");
+            }
+        }
+        let Some(values) = values.into_iter().collect::<Option<Vec<V>>>() else {
+            panic!("Row length and columns amount mismatch");
+        };
+        if !values.is_empty() {
+            let row = self.table.rows_len();
+            self.apply(Operation::InsertRow { row, values });
+        }
+    }
+
+    /// Add a row to the table using a generator function that returns the value from the column
+    /// key.
+    pub fn push_row_with<F>(&mut self, row_generator: F)
+    where
+        F: FnMut(&K) -> V,
+    {
+        let rows = self.table.rows_len();
+        self.table.push_row_with(row_generator);
+        if self.table.rows_len() > rows {
+            self.record(Operation::RemoveRow { row: rows });
+        }
+    }
+
+    /// Remove a row, returning its values.
+    ///
+    /// The values are kept in the history to undo the removal, so they are only borrowed.
+    pub fn remove_row(&mut self, row: usize) -> Option<HashTableRowBorrowed<'_, K, V>> {
+        if row >= self.table.rows_len() {
+            return None;
+        }
+        self.apply(Operation::RemoveRow { row });
+        let row_values = match self.last_recorded() {
+            Operation::InsertRow { values, .. } => values,
+            _ => unreachable!("Removing a row is reverted by inserting it"),
+        };
+        Some(HashTableRowBorrowed {
+            indices_table: &self.table.indices_table,
+            row_values,
+        })
+    }
+
+    /// Add a column with values provided through an iterator.
+    ///
+    /// See [`HashTable::insert_column`].
+    pub fn insert_column<I>(&mut self, column: K, values: I)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let index = self.table.columns_len();
+        self.table.insert_column(column, values);
+        self.record(Operation::RemoveColumn { index });
+    }
+
+    /// Remove a column, returning its values.
+    ///
+    /// The values are kept in the history to undo the removal, so they are only borrowed.
+    pub fn remove_column<Q>(&mut self, column: &Q) -> Option<&[V]>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.table.column_index(column)?;
+        self.apply(Operation::RemoveColumn { index });
+        match self.last_recorded() {
+            Operation::InsertColumn { values, .. } => Some(values),
+            _ => unreachable!("Removing a column is reverted by inserting it"),
+        }
+    }
+}
+
+impl<K, V, Q> Index<(&Q, usize)> for JournaledHashTable<K, V>
+where
+    K: Hash + Eq,
+    K: Borrow<Q>,
+    Q: Hash + Eq,
+{
+    type Output = V;
+
+    fn index(&self, index: (&Q, usize)) -> &Self::Output {
+        self.get(index.0, index.1).unwrap()
+    }
+}
+
+impl<K, V, Q> IndexMut<(&Q, usize)> for JournaledHashTable<K, V>
+where
+    K: Hash + Eq,
+    K: Borrow<Q>,
+    Q: Hash + Eq,
+    V: Clone,
+{
+    fn index_mut(&mut self, index: (&Q, usize)) -> &mut Self::Output {
+        self.get_mut(index.0, index.1).unwrap()
+    }
+}
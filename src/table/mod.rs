@@ -2,6 +2,7 @@
 
 use std::{
     borrow::Borrow,
+    cmp::Ordering,
     collections::BTreeMap,
     hash::Hash,
     ops::{Deref, Index, IndexMut},
@@ -13,13 +14,24 @@ use crate::{
         borrowed::HashTableRowBorrowed, mutable::HashTableMutableBorrowedRow,
         value_owned::HashTableRowValueOwned,
     },
-    typedefs::Keys,
+    typedefs::{HashSet, Keys},
     HashMap,
 };
 
+pub mod combine;
+pub mod computed;
+pub mod concurrent;
+pub mod diff;
+pub mod entry;
 pub mod iter;
+pub mod journal;
+pub mod observable;
 #[cfg(feature = "serde")]
 pub mod serde_impls;
+pub mod set_ops;
+pub mod shared;
+pub mod typed;
+pub mod window;
 
 /// This data structure represents a 2-dimensional grid of values. Each element is indexed by a
 /// hashable key and a row index. It's also possible to access a whole row or column of the table.
@@ -73,7 +85,10 @@ impl<K, V> HashTable<K, V> {
     /// Returns the number of rows in this table.
     #[inline(always)]
     pub fn rows_len(&self) -> usize {
-        self.values_vector.len() / self.columns_len()
+        self.values_vector
+            .len()
+            .checked_div(self.columns_len())
+            .unwrap_or(0)
     }
 
     /// Create new [`HashTable`] with specified amoutn of reserved capacity.
@@ -122,6 +137,33 @@ impl<K, V> HashTable<K, V> {
         }
     }
 
+    /// Get several rows with mutable access at once.
+    ///
+    /// Returns None if a `row` is bigger than or equal to the number of rows or the same row is
+    /// requested more than once.
+    pub fn get_rows_mut<const N: usize>(
+        &mut self,
+        rows: [usize; N],
+    ) -> Option<[HashTableMutableBorrowedRow<'_, K, V>; N]> {
+        let rows_len = self.rows_len();
+        if rows.iter().any(|row| *row >= rows_len) {
+            return None;
+        }
+        if (1..N).any(|i| rows[..i].contains(&rows[i])) {
+            return None;
+        }
+        let ranges = rows.map(|row| {
+            let start = self.row_start(row);
+            start..start + self.columns_len()
+        });
+        let indices_table = &self.indices_table;
+        let values = self.values_vector.get_disjoint_mut(ranges).ok()?;
+        Some(values.map(|values| HashTableMutableBorrowedRow {
+            indices_table,
+            values,
+        }))
+    }
+
     /// Remove a row and take ownership of its values.
     ///
     /// This still borrows the hashtable immutably to allow getting the values by a key. Keys can
@@ -130,8 +172,8 @@ impl<K, V> HashTable<K, V> {
         if row >= self.rows_len() {
             return None;
         }
-        let row_start = row * self.rows_len();
-        let row_end = row_start + self.rows_len();
+        let row_start = self.row_start(row);
+        let row_end = row_start + self.columns_len();
         let values = self.values_vector.drain(row_start..row_end);
         Some(HashTableRowValueOwned {
             parent_indices_table: &self.indices_table,
@@ -143,6 +185,34 @@ impl<K, V> HashTable<K, V> {
     pub fn column_keys(&self) -> Keys<'_, K, usize> {
         self.indices_table.keys()
     }
+
+    /// Get the column keys of this table ordered by their column index.
+    ///
+    /// This is the order in which values are stored in each row.
+    pub fn column_keys_in_order(&self) -> Vec<&K> {
+        let mut keys: Vec<(&K, usize)> = self.indices_table.iter().map(|(k, i)| (k, *i)).collect();
+        keys.sort_unstable_by_key(|(_, i)| *i);
+        keys.into_iter().map(|(k, _)| k).collect()
+    }
+
+    /// Rearrange the rows so that the row at `order[i]` becomes the row `i`.
+    ///
+    /// `order` must be a permutation of the row indices.
+    pub(crate) fn reorder_rows(&mut self, order: &[usize]) {
+        let columns_len = self.columns_len();
+        let mut values: Vec<Option<V>> = std::mem::take(&mut self.values_vector)
+            .into_iter()
+            .map(Option::Some)
+            .collect();
+        self.values_vector = order
+            .iter()
+            .flat_map(|row| {
+                let start = row * columns_len;
+                (start..start + columns_len).collect::<Vec<_>>()
+            })
+            .map(|i| values[i].take().expect("Each row is used once"))
+            .collect();
+    }
 }
 
 impl<K, V> HashTable<K, V>
@@ -199,7 +269,7 @@ where
 
     /// Index of a column.
     #[inline]
-    fn column_index<Q>(&self, column: &Q) -> Option<usize>
+    pub(crate) fn column_index<Q>(&self, column: &Q) -> Option<usize>
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
@@ -243,6 +313,39 @@ where
         self.values_vector.get_mut(idx)
     }
 
+    /// Get mutable references to several elements of the table at once.
+    ///
+    /// Will return None if a `column` does not exist in the table, a `row` is out of range or
+    /// the same element is requested more than once.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let mut table = HashTable::from_column_keys_and_rows(["a", "b"], [[1, 2], [3, 4]]);
+    /// let [a, b] = table.get_many_mut([(&"a", 0), (&"b", 1)]).unwrap();
+    /// std::mem::swap(a, b);
+    /// assert_eq!(table.get(&"a", 0), Some(&4));
+    /// assert!(table.get_many_mut([(&"a", 0), (&"a", 0)]).is_none());
+    /// ```
+    pub fn get_many_mut<Q, const N: usize>(
+        &mut self,
+        elements: [(&Q, usize); N],
+    ) -> Option<[&mut V; N]>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let rows = self.rows_len();
+        let mut indices = [0; N];
+        for (index, (column, row)) in indices.iter_mut().zip(elements) {
+            if row >= rows {
+                return None;
+            }
+            *index = self.elem_index(column, row)?;
+        }
+        self.values_vector.get_disjoint_mut(indices).ok()
+    }
+
     /// Get a table column.
     ///
     /// Will return None if the `column` does not exist in the table.
@@ -294,100 +397,307 @@ where
     /// The iterator must have the same amount of elements as there are currently rows in the
     /// table.
     ///
-    /// This function has O(n^2) complexity, where n is the number of rows. It is recommended to
-    /// insert the needed columns beforehand.
+    /// See [`Self::insert_columns`] for details.
+    #[inline]
     pub fn insert_column<I>(&mut self, column: K, values: I)
     where
         I: IntoIterator<Item = V>,
     {
-        let mut values = values.into_iter();
-        let rows = self.rows_len();
-        let new_column_index = self.columns_len();
-        self.indices_table.insert(column, new_column_index);
-        for i in 0..rows {
-            let new_elem_index = (i + 1) * new_column_index;
-            self.values_vector.insert(
-                new_elem_index,
-                values
-                    .next()
-                    .unwrap_or_else(|| panic!("The iterator must have at least {rows} rows")),
-            )
+        self.insert_columns([(column, values)])
+    }
+
+    /// Add several columns with values provided through iterators of `(key, values)` pairs.
+    ///
+    /// Every iterator of values must have the same amount of elements as there are currently rows
+    /// in the table. If the table has no columns yet, the amount of rows is defined by the first
+    /// inserted column.
+    ///
+    /// The values of the table are rebuilt in a single pass, so this function has O(r * c)
+    /// complexity, where r is the number of rows and c is the number of columns after insertion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column key is already present in the table or if the amount of values of a
+    /// column doesn't match the amount of rows. The table is not changed in that case.
+    pub fn insert_columns<I, C>(&mut self, columns: I)
+    where
+        I: IntoIterator<Item = (K, C)>,
+        C: IntoIterator<Item = V>,
+    {
+        let old_columns_len = self.columns_len();
+        let new_columns: Vec<(K, Vec<V>)> = columns
+            .into_iter()
+            .map(|(key, values)| (key, values.into_iter().collect()))
+            .collect();
+        let mut new_keys = HashSet::with_capacity(new_columns.len());
+        let has_duplicate = new_columns
+            .iter()
+            .any(|(key, _)| self.indices_table.contains_key(key) || !new_keys.insert(key));
+        drop(new_keys);
+        if has_duplicate {
+            panic!("Column is already present in the table");
+        }
+        let Some((_, first_values)) = new_columns.first() else {
+            return;
+        };
+
+        // A table without columns doesn't know its amount of rows, so the first inserted column
+        // defines it.
+        let rows = if old_columns_len == 0 {
+            first_values.len()
+        } else {
+            self.rows_len()
+        };
+        if let Some(i) = new_columns
+            .iter()
+            .position(|(_, values)| values.len() != rows)
+        {
+            panic!("Inserted column {i} must have exactly {rows} values");
+        }
+
+        let mut new_values = Vec::with_capacity(new_columns.len());
+        for (i, (key, values)) in new_columns.into_iter().enumerate() {
+            self.indices_table.insert(key, old_columns_len + i);
+            new_values.push(values.into_iter());
+        }
+        let mut old_values = std::mem::take(&mut self.values_vector).into_iter();
+        let mut values_vector = Vec::with_capacity(rows * self.columns_len());
+        for _ in 0..rows {
+            values_vector.extend(old_values.by_ref().take(old_columns_len));
+            values_vector.extend(
+                new_values
+                    .iter_mut()
+                    .map(|column| column.next().expect("Column has a value for every row")),
+            );
         }
+        self.values_vector = values_vector;
     }
 
     /// Add a column using a generator function that returns a value based on the values of the
     /// row.
     ///
-    /// This function has O(n^2) complexity, where n is the number of rows. It is recommended to
-    /// insert the needed columns beforehand.
+    /// The generated values are inserted with [`Self::insert_columns`], so this function has
+    /// O(r * c) complexity, where r is the number of rows and c is the number of columns.
     pub fn insert_column_with<F>(&mut self, column: K, mut values: F)
     where
         F: FnMut(HashTableRowBorrowed<'_, K, V>) -> V,
     {
-        let rows = self.rows_len();
-        self.insert_column(
-            column,
-            (0..rows)
-                .map(|i| {
-                    let row = self.get_row(i).unwrap();
-                    values(row)
-                })
-                .collect::<Vec<_>>(),
+        let values: Vec<V> = self.iter().map(&mut values).collect();
+        self.insert_column(column, values);
+    }
+
+    /// Replace the values of a column with values provided through an iterator, returning the
+    /// previous values.
+    ///
+    /// Returns `None` if there is no such column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount of values doesn't match the amount of rows. The table is not changed
+    /// in that case.
+    pub fn replace_column_values<Q, I>(&mut self, column: &Q, values: I) -> Option<Vec<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        I: IntoIterator<Item = V>,
+    {
+        let index = self.column_index(column)?;
+        let values: Vec<V> = values.into_iter().collect();
+        assert!(
+            values.len() == self.rows_len(),
+            "Column must have a value for every row"
         );
+        let columns_len = self.columns_len();
+        Some(
+            self.values_vector
+                .chunks_exact_mut(columns_len)
+                .zip(values)
+                .map(|(row, value)| std::mem::replace(&mut row[index], value))
+                .collect(),
+        )
     }
 
     /// Remove a column from the table and take ownership of the key and values.
     ///
     /// Will return None if the `column` does not exist in the table.
+    ///
+    /// See [`Self::remove_columns`] for details.
+    #[inline]
     pub fn remove_column<Q>(&mut self, column: &Q) -> Option<HashTableColumnOwned<K, V>>
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        if !self.indices_table.contains_key(column) {
-            return None;
-        }
+        self.remove_columns([column]).pop()
+    }
+
+    /// Remove several columns from the table and take ownership of their keys and values.
+    ///
+    /// Columns are returned in the order they were requested in. Keys that are not present in the
+    /// table are skipped.
+    ///
+    /// The values of the table are rebuilt in a single pass, so this function has O(r * c)
+    /// complexity, where r is the number of rows and c is the number of columns before removal.
+    pub fn remove_columns<'q, Q, I>(&mut self, columns: I) -> Vec<HashTableColumnOwned<K, V>>
+    where
+        I: IntoIterator<Item = &'q Q>,
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'q,
+    {
+        let old_columns_len = self.columns_len();
         let rows = self.rows_len();
-        let (key, column_index) = self.indices_table.remove_entry(column).unwrap();
-        for v in self.indices_table.values_mut() {
-            if *v > column_index {
-                *v -= 1;
+
+        // Position in `removed` for each of the old column indices that is being removed
+        let mut removed_slots: Vec<Option<usize>> = vec![None; old_columns_len];
+        let mut removed = Vec::new();
+        for column in columns {
+            if let Some((key, index)) = self.indices_table.remove_entry(column) {
+                removed_slots[index] = Some(removed.len());
+                removed.push(HashTableColumnOwned {
+                    key,
+                    values: Vec::with_capacity(rows),
+                });
             }
         }
-        let mut buf = Vec::with_capacity(self.rows_len());
-        for i in 0..rows {
-            let index = i * self.columns_len() + column_index;
-            buf.push(self.values_vector.remove(index));
+        if removed.is_empty() {
+            return removed;
+        }
+
+        let new_indices: Vec<usize> = removed_slots
+            .iter()
+            .scan(0, |kept_before, slot| {
+                let new_index = *kept_before;
+                if slot.is_none() {
+                    *kept_before += 1;
+                }
+                Some(new_index)
+            })
+            .collect();
+        for index in self.indices_table.values_mut() {
+            *index = new_indices[*index];
+        }
+
+        let mut values_vector = Vec::with_capacity(rows * self.columns_len());
+        for (i, value) in std::mem::take(&mut self.values_vector)
+            .into_iter()
+            .enumerate()
+        {
+            match removed_slots[i % old_columns_len] {
+                Some(slot) => removed[slot].values.push(value),
+                None => values_vector.push(value),
+            }
         }
-        Some(HashTableColumnOwned { key, values: buf })
+        self.values_vector = values_vector;
+
+        removed
     }
 
-    /// Construct HashTable from an iterator of columns
-    pub fn from_column_iter<I, C>(iter: I) -> Self
+    /// Keep only the rows for which the `predicate` returns `true`.
+    pub fn retain<F>(&mut self, mut predicate: F)
     where
-        I: IntoIterator<Item = C>,
-        C: Into<HashTableColumnOwned<K, V>>,
+        F: FnMut(HashTableRowBorrowed<'_, K, V>) -> bool,
+    {
+        let keep: Vec<bool> = self.iter().map(&mut predicate).collect();
+        let columns_len = self.columns_len();
+        let mut i = 0;
+        self.values_vector.retain(|_| {
+            let keep_value = keep[i / columns_len];
+            i += 1;
+            keep_value
+        });
+    }
+
+    /// Copy the rows for which the `predicate` returns `true` into a new table.
+    pub fn filter<F>(&self, mut predicate: F) -> Self
+    where
+        F: FnMut(HashTableRowBorrowed<'_, K, V>) -> bool,
+        K: Clone,
+        V: Clone,
     {
-        let mut indices = HashMap::new();
-        let mut result_values = Vec::new();
-        let mut expected_length = None;
-        for (i, col) in iter.into_iter().map(Into::into).enumerate() {
-            let (key, col_values) = col.into_pair();
-            let expected_length = expected_length.get_or_insert(col_values.len());
-            if col_values.len() != *expected_length {
-                panic!("Column {i} doesn't have the same amopunt of elements as the first column");
+        let mut values_vector = Vec::new();
+        for row in self {
+            if predicate(row) {
+                values_vector.extend_from_slice(row.row_values);
             }
-            indices.insert(key, i);
-            for (row, val) in col_values.into_iter().enumerate() {
-                result_values.insert((row + 1) * (i + 1), val);
+        }
+        Self {
+            indices_table: self.indices_table.clone(),
+            values_vector,
+        }
+    }
+
+    /// Copy the requested columns into a new table.
+    ///
+    /// Columns are placed in the new table in the order they were requested in. Keys that are not
+    /// present in the table are skipped.
+    pub fn select<'q, Q, I>(&self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = &'q Q>,
+        K: Borrow<Q> + Clone,
+        Q: Hash + Eq + ?Sized + 'q,
+        V: Clone,
+    {
+        let mut indices_table = HashMap::new();
+        let mut source_indices = Vec::new();
+        for column in columns {
+            if let Some((key, index)) = self.indices_table.get_key_value(column) {
+                if !indices_table.contains_key(key) {
+                    indices_table.insert(key.clone(), source_indices.len());
+                    source_indices.push(*index);
+                }
             }
         }
+        let mut values_vector = Vec::with_capacity(source_indices.len() * self.rows_len());
+        for row in self {
+            values_vector.extend(source_indices.iter().map(|i| row.row_values[*i].clone()));
+        }
         Self {
-            indices_table: indices,
-            values_vector: result_values,
+            indices_table,
+            values_vector,
         }
     }
 
+    /// Sort the rows of the table with a comparator function.
+    ///
+    /// The sort is stable.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(HashTableRowBorrowed<'_, K, V>, HashTableRowBorrowed<'_, K, V>) -> Ordering,
+    {
+        let mut order: Vec<usize> = (0..self.rows_len()).collect();
+        order.sort_by(|a, b| compare(self.get_row(*a).unwrap(), self.get_row(*b).unwrap()));
+        self.reorder_rows(&order);
+    }
+
+    /// Sort the rows of the table by a key extracted from each row.
+    ///
+    /// The sort is stable.
+    pub fn sort_by_key<T, F>(&mut self, mut key: F)
+    where
+        F: FnMut(HashTableRowBorrowed<'_, K, V>) -> T,
+        T: Ord,
+    {
+        let keys: Vec<T> = self.iter().map(&mut key).collect();
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+        self.reorder_rows(&order);
+    }
+
+    /// Construct HashTable from an iterator of columns
+    ///
+    /// # Panics
+    ///
+    /// Panics if the columns have different lengths or if a column key repeats.
+    pub fn from_column_iter<I, C>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<HashTableColumnOwned<K, V>>,
+    {
+        let mut table = Self::default();
+        table.insert_columns(iter.into_iter().map(|col| col.into().into_pair()));
+        table
+    }
+
     /// Construct a [`HashTable`] from an iterator of column keys and an iterator of rows of values
     ///
     /// The only check of row lengths performed is whether there is a remainder from division of
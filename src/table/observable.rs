@@ -0,0 +1,241 @@
+//! Tables that notify observers about their changes
+
+use std::{borrow::Borrow, fmt};
+
+use crate::{
+    column::owned::HashTableColumnOwned, row::value_owned::HashTableRowValueOwned, typedefs::*,
+    HashTable,
+};
+
+/// A change of an [`ObservableHashTable`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableEvent<'e, K> {
+    /// A row was added at this index
+    RowInserted { row: usize },
+    /// The row at this index was removed, the following rows were moved one index back
+    RowRemoved { row: usize },
+    /// The value in the `column` of the `row` was changed
+    CellUpdated { row: usize, column: &'e K },
+    /// A column was added
+    ColumnAdded { column: &'e K },
+    /// A column was removed
+    ColumnRemoved { column: &'e K },
+}
+
+/// Identifier of an observer, used to remove it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(usize);
+
+type Observer<K> = Box<dyn FnMut(&TableEvent<'_, K>)>;
+
+/// A table that calls observers after each change made through it.
+///
+/// Values can only be changed with methods that report the change afterwards, such as
+/// [`Self::set`] and [`Self::update`], so observers always see the new state of the table.
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::{HashTable, table::observable::{ObservableHashTable, TableEvent}};
+/// # use std::{cell::RefCell, rc::Rc};
+/// let mut table = ObservableHashTable::from(HashTable::with_columns(["x", "y"]));
+/// let dirty = Rc::new(RefCell::new(Vec::new()));
+/// let log = Rc::clone(&dirty);
+/// table.subscribe(move |event| {
+///     if let TableEvent::CellUpdated { row, column } = event {
+///         log.borrow_mut().push((*row, **column));
+///     }
+/// });
+///
+/// table.push_row_with(|_| 0);
+/// table.set("y", 0, 5);
+/// table.update("x", 0, |x| *x += 1);
+/// assert_eq!(*dirty.borrow(), [(0, "y"), (0, "x")]);
+/// ```
+pub struct ObservableHashTable<K, V> {
+    table: HashTable<K, V>,
+    observers: Vec<(ObserverId, Observer<K>)>,
+    next_id: usize,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ObservableHashTable<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservableHashTable")
+            .field("table", &self.table)
+            .field(
+                "observers",
+                &self.observers.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<K, V> From<HashTable<K, V>> for ObservableHashTable<K, V> {
+    fn from(table: HashTable<K, V>) -> Self {
+        Self {
+            table,
+            observers: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<K, V> Default for ObservableHashTable<K, V> {
+    fn default() -> Self {
+        HashTable::default().into()
+    }
+}
+
+/// Call every observer with the `event`
+fn notify<K>(observers: &mut [(ObserverId, Observer<K>)], event: TableEvent<'_, K>) {
+    for (_, observer) in observers {
+        observer(&event);
+    }
+}
+
+impl<K, V> ObservableHashTable<K, V> {
+    /// Get the underlying table.
+    pub fn table(&self) -> &HashTable<K, V> {
+        &self.table
+    }
+
+    /// Take the underlying table, dropping the observers.
+    pub fn into_table(self) -> HashTable<K, V> {
+        self.table
+    }
+
+    /// Register a function to call after every change of the table.
+    pub fn subscribe<F>(&mut self, observer: F) -> ObserverId
+    where
+        F: FnMut(&TableEvent<'_, K>) + 'static,
+    {
+        let id = ObserverId(self.next_id);
+        self.next_id += 1;
+        self.observers.push((id, Box::new(observer)));
+        id
+    }
+
+    /// Remove an observer. Returns `false` if it was already removed.
+    pub fn unsubscribe(&mut self, id: ObserverId) -> bool {
+        let len = self.observers.len();
+        self.observers.retain(|(observer, _)| *observer != id);
+        self.observers.len() != len
+    }
+
+    /// Remove a row and take ownership of its values.
+    pub fn remove_row(&mut self, row: usize) -> Option<HashTableRowValueOwned<'_, K, V>> {
+        let values = self.table.remove_row(row)?.values;
+        notify(&mut self.observers, TableEvent::RowRemoved { row });
+        Some(HashTableRowValueOwned {
+            parent_indices_table: &self.table.indices_table,
+            values,
+        })
+    }
+}
+
+impl<K, V> ObservableHashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Get a reference to a value in the table.
+    pub fn get<Q>(&self, column: &Q, row: usize) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.table.get(column, row)
+    }
+
+    /// Change a value in the table with `f`.
+    ///
+    /// Returns `None` if there is no such column or row.
+    pub fn update<Q, R, F>(&mut self, column: &Q, row: usize, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&mut V) -> R,
+    {
+        let (key, index) = self.table.indices_table.get_key_value(column)?;
+        if row >= self.table.rows_len() {
+            return None;
+        }
+        let start = self.table.row_start(row);
+        let result = f(&mut self.table.values_vector[start + index]);
+        notify(
+            &mut self.observers,
+            TableEvent::CellUpdated { row, column: key },
+        );
+        Some(result)
+    }
+
+    /// Replace a value in the table, returning the previous value.
+    ///
+    /// Returns `None` and drops the `value` if there is no such column or row.
+    pub fn set<Q>(&mut self, column: &Q, row: usize, value: V) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.update(column, row, |old| std::mem::replace(old, value))
+    }
+
+    /// Add a row to the table.
+    ///
+    /// See [`HashTable::push_row`].
+    pub fn push_row<I>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let rows = self.table.rows_len();
+        self.table.push_row(row);
+        if self.table.rows_len() > rows {
+            notify(&mut self.observers, TableEvent::RowInserted { row: rows });
+        }
+    }
+
+    /// Add a row to the table using a generator function that returns the value from the column
+    /// key.
+    pub fn push_row_with<F>(&mut self, row_generator: F)
+    where
+        F: FnMut(&K) -> V,
+    {
+        let rows = self.table.rows_len();
+        self.table.push_row_with(row_generator);
+        if self.table.rows_len() > rows {
+            notify(&mut self.observers, TableEvent::RowInserted { row: rows });
+        }
+    }
+
+    /// Add a column with values provided through an iterator.
+    ///
+    /// See [`HashTable::insert_column`].
+    pub fn insert_column<I>(&mut self, column: K, values: I)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let index = self.table.columns_len();
+        self.table.insert_column(column, values);
+        let column = self
+            .table
+            .indices_table
+            .iter()
+            .find_map(|(k, i)| (*i == index).then_some(k))
+            .expect("Column was just inserted");
+        notify(&mut self.observers, TableEvent::ColumnAdded { column });
+    }
+
+    /// Remove a column and take ownership of its key and values.
+    pub fn remove_column<Q>(&mut self, column: &Q) -> Option<HashTableColumnOwned<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let column = self.table.remove_column(column)?;
+        notify(
+            &mut self.observers,
+            TableEvent::ColumnRemoved {
+                column: column.key(),
+            },
+        );
+        Some(column)
+    }
+}
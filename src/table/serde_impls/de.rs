@@ -1,12 +1,13 @@
 use std::marker::PhantomData;
 
-use serde::{de::Visitor, Deserialize, Deserializer};
-
-use crate::{
-    typedefs::{Hash, HashMap},
-    HashTable,
+use serde::{
+    de::{DeserializeSeed, Error, IgnoredAny, Visitor},
+    Deserialize, Deserializer,
 };
 
+use super::key_name::{column, NamedKey, NamedKeys};
+use crate::{column::owned::HashTableColumnOwned, typedefs::Hash, HashTable};
+
 impl<'de, K, V> Deserialize<'de> for HashTable<K, V>
 where
     K: Hash + Eq,
@@ -15,6 +16,9 @@ where
 {
     /// Deserializes a [`HashTable`] from sequence of key-value maps
     ///
+    /// The keys of the first row define the columns, in the order they appear. Every other row
+    /// must have exactly the same keys, in any order.
+    ///
     /// Will fall back to [`deserialize_hashtable_from_map`] if deserializer decides to provide a
     /// map
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -43,15 +47,19 @@ where
     where
         A: serde::de::SeqAccess<'de>,
     {
-        let mut res = match seq.size_hint() {
-            None => HashTable::default(),
-            Some(len) => HashTable::with_capacity(0, len),
-        };
-
-        while let Some(row) = seq.next_element::<HashMap<K, V>>()? {
-            res.push_row(row);
+        let mut res = HashTable::default();
+        let mut names = Vec::new();
+        let mut row = 0;
+        while seq
+            .next_element_seed(RowSeed {
+                table: &mut res,
+                names: &mut names,
+                row,
+            })?
+            .is_some()
+        {
+            row += 1;
         }
-
         Ok(res)
     }
 
@@ -63,8 +71,99 @@ where
     }
 }
 
+/// Deserializes one row map and appends it to the `table`, defining the columns if it's the first
+/// row.
+///
+/// `names` holds the names of the columns, as written in the first row, for error messages.
+struct RowSeed<'t, K, V> {
+    table: &'t mut HashTable<K, V>,
+    names: &'t mut Vec<Option<String>>,
+    row: usize,
+}
+
+impl<'de, K, V> DeserializeSeed<'de> for RowSeed<'_, K, V>
+where
+    K: Hash + Eq,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, K, V> Visitor<'de> for RowSeed<'_, K, V>
+where
+    K: Hash + Eq,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a map of column key to value")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let Self { table, names, row } = self;
+
+        // Keys are named in errors as they were written if they are scalars, and by their column
+        // index or position in the row otherwise, as they may not be printable
+        let mut name = None;
+        if row == 0 {
+            while let Some(key) = map.next_key_seed(NamedKey::<K>::new(&mut name))? {
+                if let Some(index) = table.indices_table.get(&key) {
+                    let column = column(name.as_deref(), index);
+                    return Err(A::Error::custom(format!(
+                        "row {row} has duplicate {column}"
+                    )));
+                }
+                let value = map.next_value()?;
+                let index = table.indices_table.len();
+                table.indices_table.insert(key, index);
+                table.values_vector.push(value);
+                names.push(name.take());
+            }
+            return Ok(());
+        }
+
+        let mut values: Vec<Option<V>> = (0..table.columns_len()).map(|_| None).collect();
+        let mut position = 0;
+        while let Some(key) = map.next_key_seed(NamedKey::<K>::new(&mut name))? {
+            let Some(&index) = table.indices_table.get(&key) else {
+                let column = column(name.as_deref(), format_args!("at position {position}"));
+                return Err(A::Error::custom(format!("row {row} has unknown {column}")));
+            };
+            if values[index].is_some() {
+                let column = column(name.as_deref(), index);
+                return Err(A::Error::custom(format!(
+                    "row {row} has duplicate {column}"
+                )));
+            }
+            values[index] = Some(map.next_value()?);
+            position += 1;
+        }
+        if let Some(missing) = values.iter().position(Option::is_none) {
+            let column = column(names[missing].as_deref(), missing);
+            return Err(A::Error::custom(format!("row {row} is missing {column}")));
+        }
+        table.values_vector.extend(values.into_iter().flatten());
+        Ok(())
+    }
+}
+
 /// A function to use in `#[serde(deserialize_with = "...")]`
 ///
+/// Every column must have the same number of values.
+///
 /// Will fall back to the row-wise deserialization if the deserializer decides to deserialize a
 /// sequence
 pub fn deserialize_hashtable_from_map<'de, K, V, D>(des: D) -> Result<HashTable<K, V>, D::Error>
@@ -95,13 +194,38 @@ where
     where
         A: serde::de::MapAccess<'de>,
     {
-        let mut res = match map.size_hint() {
-            None => HashTable::default(),
-            Some(len) => HashTable::with_capacity(len, 0),
-        };
+        let mut res = HashTable::default();
+        let mut columns = Vec::with_capacity(map.size_hint().unwrap_or(0));
+
+        let mut name = None;
+        while let Some(key) = map.next_key_seed(NamedKey::<K>::new(&mut name))? {
+            if let Some(index) = res.indices_table.get(&key) {
+                let column = column(name.as_deref(), index);
+                return Err(A::Error::custom(format!("duplicate {column}")));
+            }
+            let values: Vec<V> = map.next_value()?;
+            if let Some(first) = columns.first().map(Vec::len) {
+                if values.len() != first {
+                    let column = column(name.as_deref(), columns.len());
+                    return Err(A::Error::custom(format!(
+                        "{column} has {} values, expected {first}",
+                        values.len()
+                    )));
+                }
+            }
+            res.indices_table.insert(key, columns.len());
+            columns.push(values);
+        }
 
-        while let Some((key, values)) = map.next_entry::<K, Vec<V>>()? {
-            res.insert_column(key, values);
+        let rows = columns.first().map_or(0, Vec::len);
+        let mut columns: Vec<_> = columns.into_iter().map(Vec::into_iter).collect();
+        res.values_vector.reserve(rows * columns.len());
+        for _ in 0..rows {
+            res.values_vector.extend(
+                columns
+                    .iter_mut()
+                    .map(|c| c.next().expect("Column lengths are checked")),
+            );
         }
 
         Ok(res)
@@ -114,3 +238,275 @@ where
         HashTableVisitor(PhantomData).visit_seq(seq)
     }
 }
+
+/// A function to use in `#[serde(deserialize_with = "...")]`
+///
+/// Deserializes a table written by [`serialize_hashtable_as_header_rows`]. Every row must have
+/// exactly one value per column.
+///
+/// Rows are appended to the table as they are read, without collecting them first.
+///
+/// [`serialize_hashtable_as_header_rows`]: super::ser::serialize_hashtable_as_header_rows
+pub fn deserialize_hashtable_from_header_rows<'de, K, V, D>(
+    des: D,
+) -> Result<HashTable<K, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Hash + Eq,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    des.deserialize_struct(
+        "HashTable",
+        &["columns", "rows"],
+        HeaderRowsVisitor(PhantomData),
+    )
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum HeaderRowsField {
+    Columns,
+    Rows,
+    #[serde(other)]
+    Other,
+}
+
+struct HeaderRowsVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<K, V> HeaderRowsVisitor<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Add the column keys to the `table`, checking them against the length of the rows read
+    /// before them.
+    fn set_columns<E: Error>(
+        table: &mut HashTable<K, V>,
+        columns: NamedKeys<K>,
+        row_len: Option<usize>,
+    ) -> Result<(), E> {
+        let NamedKeys { keys, names } = columns;
+        let columns_len = keys.len();
+        table.indices_table.reserve(columns_len);
+        for (i, key) in keys.into_iter().enumerate() {
+            if table.indices_table.insert(key, i).is_some() {
+                let column = column(names[i].as_deref(), format_args!("at index {i}"));
+                return Err(E::custom(format!("duplicate {column}")));
+            }
+        }
+        match row_len {
+            Some(row_len) if row_len != columns_len => Err(E::custom(format!(
+                "row 0 has {row_len} values, expected {columns_len}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'de, K, V> Visitor<'de> for HeaderRowsVisitor<K, V>
+where
+    K: Hash + Eq,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = HashTable<K, V>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a struct with `columns` and `rows`")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut res = HashTable::default();
+        let mut has_columns = false;
+        let mut has_rows = false;
+        let mut row_len = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                HeaderRowsField::Columns => {
+                    if has_columns {
+                        return Err(A::Error::duplicate_field("columns"));
+                    }
+                    has_columns = true;
+                    Self::set_columns(&mut res, map.next_value()?, row_len)?;
+                }
+                HeaderRowsField::Rows => {
+                    if has_rows {
+                        return Err(A::Error::duplicate_field("rows"));
+                    }
+                    has_rows = true;
+                    row_len = map.next_value_seed(RowsSeed {
+                        values: &mut res.values_vector,
+                        row_len: has_columns.then_some(res.indices_table.len()),
+                    })?;
+                }
+                HeaderRowsField::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        if !has_columns {
+            return Err(A::Error::missing_field("columns"));
+        }
+        if !has_rows {
+            return Err(A::Error::missing_field("rows"));
+        }
+        Ok(res)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut res = HashTable::default();
+        let columns = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        Self::set_columns(&mut res, columns, None)?;
+        let rows = RowsSeed {
+            values: &mut res.values_vector,
+            row_len: Some(res.indices_table.len()),
+        };
+        seq.next_element_seed(rows)?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        Ok(res)
+    }
+}
+
+/// Deserializes a sequence of rows and appends their values to `values`.
+///
+/// Every row must have `row_len` values, or as many values as the first row if it's `None`.
+/// Returns the length of the rows, `None` if there are no rows and it wasn't known.
+struct RowsSeed<'t, V> {
+    values: &'t mut Vec<V>,
+    row_len: Option<usize>,
+}
+
+impl<'de, V> DeserializeSeed<'de> for RowsSeed<'_, V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = Option<usize>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, V> Visitor<'de> for RowsSeed<'_, V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = Option<usize>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a sequence of rows")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let Self {
+            values,
+            mut row_len,
+        } = self;
+        let mut row = 0;
+        while let Some(len) = seq.next_element_seed(ValuesSeed {
+            values: &mut *values,
+            row,
+            row_len,
+        })? {
+            row_len = Some(len);
+            row += 1;
+        }
+        Ok(row_len)
+    }
+}
+
+/// Deserializes one row and appends its values to `values`, returning the number of values.
+struct ValuesSeed<'t, V> {
+    values: &'t mut Vec<V>,
+    row: usize,
+    row_len: Option<usize>,
+}
+
+impl<'de, V> DeserializeSeed<'de> for ValuesSeed<'_, V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = usize;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, V> Visitor<'de> for ValuesSeed<'_, V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a sequence of row values")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let Self {
+            values,
+            row,
+            row_len,
+        } = self;
+        let mut len = 0;
+        while row_len != Some(len) {
+            let Some(value) = seq.next_element()? else {
+                break;
+            };
+            values.push(value);
+            len += 1;
+        }
+        match row_len {
+            Some(row_len) if len < row_len => Err(A::Error::custom(format!(
+                "row {row} has {len} values, expected {row_len}"
+            ))),
+            Some(row_len) if seq.next_element::<IgnoredAny>()?.is_some() => Err(A::Error::custom(
+                format!("row {row} has more than {row_len} values"),
+            )),
+            _ => Ok(len),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "HashTableColumn")]
+struct ColumnFields<K, V> {
+    key: K,
+    values: Vec<V>,
+}
+
+impl<'de, K, V> Deserialize<'de> for HashTableColumnOwned<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    /// Deserializes a column from a struct with the column `key` and a sequence of `values`
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ColumnFields { key, values } = ColumnFields::deserialize(deserializer)?;
+        Ok(Self { key, values })
+    }
+}
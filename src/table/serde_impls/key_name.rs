@@ -0,0 +1,290 @@
+//! Deserialization of column keys that also records how the key was written, so that errors can
+//! name the offending column without requiring the key type to be printable.
+
+use std::{fmt::Display, marker::PhantomData};
+
+use serde::{
+    de::{DeserializeSeed, EnumAccess, Error, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+/// Deserializes a `K`, storing the text of the key in `name` if it was written as a scalar, like a
+/// string or a number.
+pub(super) struct NamedKey<'n, K> {
+    name: &'n mut Option<String>,
+    key: PhantomData<K>,
+}
+
+impl<'n, K> NamedKey<'n, K> {
+    pub(super) fn new(name: &'n mut Option<String>) -> Self {
+        *name = None;
+        Self {
+            name,
+            key: PhantomData,
+        }
+    }
+}
+
+impl<'de, K> DeserializeSeed<'de> for NamedKey<'_, K>
+where
+    K: Deserialize<'de>,
+{
+    type Value = K;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        K::deserialize(NameDeserializer {
+            inner: deserializer,
+            name: self.name,
+        })
+    }
+}
+
+/// Describes a column by its `name` if it's known, or by the `fallback` otherwise
+pub(super) fn column(name: Option<&str>, fallback: impl Display) -> String {
+    match name {
+        Some(name) => format!("column `{name}`"),
+        None => format!("column {fallback}"),
+    }
+}
+
+struct NameDeserializer<'n, D> {
+    inner: D,
+    name: &'n mut Option<String>,
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {$(
+        fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.inner.$method($($arg,)* NameVisitor {
+                inner: visitor,
+                name: self.name,
+            })
+        }
+    )*};
+}
+
+impl<'de, D> Deserializer<'de> for NameDeserializer<'_, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any()
+        deserialize_bool()
+        deserialize_i8()
+        deserialize_i16()
+        deserialize_i32()
+        deserialize_i64()
+        deserialize_i128()
+        deserialize_u8()
+        deserialize_u16()
+        deserialize_u32()
+        deserialize_u64()
+        deserialize_u128()
+        deserialize_f32()
+        deserialize_f64()
+        deserialize_char()
+        deserialize_str()
+        deserialize_string()
+        deserialize_bytes()
+        deserialize_byte_buf()
+        deserialize_option()
+        deserialize_unit()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_seq()
+        deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map()
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier()
+        deserialize_ignored_any()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct NameVisitor<'n, V> {
+    inner: V,
+    name: &'n mut Option<String>,
+}
+
+macro_rules! record_visit {
+    ($($method:ident($ty:ty))*) => {$(
+        fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            *self.name = Some(v.to_string());
+            self.inner.$method(v)
+        }
+    )*};
+}
+
+impl<'de, V> Visitor<'de> for NameVisitor<'_, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    record_visit! {
+        visit_bool(bool)
+        visit_i8(i8)
+        visit_i16(i16)
+        visit_i32(i32)
+        visit_i64(i64)
+        visit_i128(i128)
+        visit_u8(u8)
+        visit_u16(u16)
+        visit_u32(u32)
+        visit_u64(u64)
+        visit_u128(u128)
+        visit_f32(f32)
+        visit_f64(f64)
+        visit_char(char)
+        visit_str(&str)
+        visit_borrowed_str(&'de str)
+        visit_string(String)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        *self.name = Some(String::from_utf8_lossy(v).into_owned());
+        self.inner.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        *self.name = Some(String::from_utf8_lossy(v).into_owned());
+        self.inner.visit_borrowed_bytes(v)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        *self.name = Some(String::from_utf8_lossy(&v).into_owned());
+        self.inner.visit_byte_buf(v)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.visit_some(NameDeserializer {
+            inner: deserializer,
+            name: self.name,
+        })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.visit_newtype_struct(NameDeserializer {
+            inner: deserializer,
+            name: self.name,
+        })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.inner.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.inner.visit_map(map)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        self.inner.visit_enum(data)
+    }
+}
+
+/// Column keys and the names they were written with, deserialized from a sequence of keys
+pub(super) struct NamedKeys<K> {
+    pub(super) keys: Vec<K>,
+    pub(super) names: Vec<Option<String>>,
+}
+
+impl<'de, K> Deserialize<'de> for NamedKeys<K>
+where
+    K: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(NamedKeysVisitor(PhantomData))
+    }
+}
+
+struct NamedKeysVisitor<K>(PhantomData<K>);
+
+impl<'de, K> Visitor<'de> for NamedKeysVisitor<K>
+where
+    K: Deserialize<'de>,
+{
+    type Value = NamedKeys<K>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a sequence of column keys")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let capacity = seq.size_hint().unwrap_or(0);
+        let mut keys = NamedKeys {
+            keys: Vec::with_capacity(capacity),
+            names: Vec::with_capacity(capacity),
+        };
+        let mut name = None;
+        while let Some(key) = seq.next_element_seed(NamedKey::new(&mut name))? {
+            keys.keys.push(key);
+            keys.names.push(name.take());
+        }
+        Ok(keys)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod de;
+mod key_name;
 pub mod ser;
 
 /// A module to use with `#[serde(with = "...")]`
@@ -8,3 +9,14 @@ pub mod hashtable_columns_map {
         ser::serialize_hashtable_as_map as serialize,
     };
 }
+
+/// A module to use with `#[serde(with = "...")]`
+///
+/// Stores column keys once, followed by rows of values in column-index order:
+/// `{ "columns": [...], "rows": [[...], ...] }`
+pub mod hashtable_header_rows {
+    pub use super::{
+        de::deserialize_hashtable_from_header_rows as deserialize,
+        ser::serialize_hashtable_as_header_rows as serialize,
+    };
+}
@@ -1,9 +1,18 @@
 use serde::{
-    ser::{SerializeMap, SerializeSeq},
+    ser::{SerializeMap, SerializeSeq, SerializeStruct},
     Serialize, Serializer,
 };
 
-use crate::{row::borrowed::HashTableRowBorrowed, HashTable};
+use crate::{
+    column::{borrowed::HashTableColumnBorrowed, owned::HashTableColumnOwned},
+    row::{
+        borrowed::HashTableRowBorrowed, mutable::HashTableMutableBorrowedRow,
+        value_owned::HashTableRowValueOwned,
+    },
+    table::computed::{ComputedHashTable, ComputedRow, ValueRef},
+    typedefs::*,
+    HashTable,
+};
 
 impl<'t, K, V> Serialize for HashTableRowBorrowed<'t, K, V>
 where
@@ -22,6 +31,76 @@ where
     }
 }
 
+/// Serializes the same way as [`HashTableRowBorrowed`]
+impl<K, V> Serialize for HashTableMutableBorrowedRow<'_, K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(self.indices_table.len()))?;
+        for (k, i) in self.indices_table.iter() {
+            state.serialize_entry(k, &self.values[*i])?;
+        }
+        state.end()
+    }
+}
+
+/// Serializes the same way as [`HashTableRowBorrowed`]
+impl<K, V> Serialize for HashTableRowValueOwned<'_, K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(self.parent_indices_table.len()))?;
+        for (k, i) in self.parent_indices_table.iter() {
+            state.serialize_entry(k, &self.values[*i])?;
+        }
+        state.end()
+    }
+}
+
+/// Serializes as a struct with the column `key` and a sequence of `values`
+impl<Q, V> Serialize for HashTableColumnBorrowed<'_, '_, Q, V>
+where
+    Q: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("HashTableColumn", 2)?;
+        state.serialize_field("key", self.column)?;
+        state.serialize_field("values", &self.values)?;
+        state.end()
+    }
+}
+
+/// Serializes as a struct with the column `key` and a sequence of `values`
+impl<K, V> Serialize for HashTableColumnOwned<K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("HashTableColumn", 2)?;
+        state.serialize_field("key", &self.key)?;
+        state.serialize_field("values", &self.values)?;
+        state.end()
+    }
+}
+
 impl<K, V> Serialize for HashTable<K, V>
 where
     K: Serialize,
@@ -39,6 +118,53 @@ where
     }
 }
 
+impl<V> Serialize for ValueRef<'_, V>
+where
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'t, K, V> Serialize for ComputedRow<'t, K, V>
+where
+    K: Serialize + Hash + Eq,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(self.columns_len()))?;
+        for (k, v) in *self {
+            state.serialize_entry(k, &v)?;
+        }
+        state.end()
+    }
+}
+
+/// Serializes the same way as [`HashTable`], with computed columns included in every row
+impl<K, V> Serialize for ComputedHashTable<K, V>
+where
+    K: Serialize + Hash + Eq,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.rows_len()))?;
+        for row in self {
+            state.serialize_element(&row)?;
+        }
+        state.end()
+    }
+}
+
 /// A function to use in `#[serde(serialize_with = "...")]`
 ///
 /// Serializes the table as a map of column keys to column values
@@ -59,3 +185,41 @@ where
 
     state.end()
 }
+
+/// Rows of a table as sequences of values in column-index order
+struct HeaderRows<'t, K, V>(&'t HashTable<K, V>);
+
+impl<K, V> Serialize for HeaderRows<'_, K, V>
+where
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let table = self.0;
+        if table.columns_len() == 0 {
+            return serializer.collect_seq(std::iter::empty::<()>());
+        }
+        serializer.collect_seq(table.values_vector.chunks(table.columns_len()))
+    }
+}
+
+/// A function to use in `#[serde(serialize_with = "...")]`
+///
+/// Serializes the table as a struct with a `columns` sequence of column keys and a `rows` sequence
+/// of value sequences, both in column-index order
+pub fn serialize_hashtable_as_header_rows<S, K, V>(
+    table: &HashTable<K, V>,
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize,
+    V: Serialize,
+{
+    let mut state = ser.serialize_struct("HashTable", 2)?;
+    state.serialize_field("columns", &table.column_keys_in_order())?;
+    state.serialize_field("rows", &HeaderRows(table))?;
+    state.end()
+}
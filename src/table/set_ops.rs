@@ -0,0 +1,221 @@
+//! Set operations on the rows of tables
+//!
+//! Rows are compared as tuples of their values in column-index order. Operations on two tables
+//! match the columns by their keys, so the tables may have different column orders.
+
+use std::borrow::Borrow;
+
+use crate::{typedefs::*, HashTable};
+
+impl<K, V> HashTable<K, V>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+{
+    /// Values of the rows of `other` in the column order of this table.
+    ///
+    /// A table without columns has no rows and can be combined with any table, as in
+    /// [`Self::append_table`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if both tables have columns and they are different sets of columns.
+    fn other_rows_in_column_order<'t>(
+        &self,
+        other: &'t Self,
+    ) -> impl Iterator<Item = Vec<&'t V>> + 't {
+        let (rows, source) = if self.columns_len() == 0 || other.columns_len() == 0 {
+            (0, Vec::new())
+        } else {
+            assert!(
+                other.columns_len() == self.columns_len(),
+                "Tables must have the same columns"
+            );
+            let source: Vec<usize> = self
+                .column_keys_in_order()
+                .into_iter()
+                .map(|key| {
+                    other
+                        .column_index::<K>(key)
+                        .expect("Tables must have the same columns")
+                })
+                .collect();
+            (other.rows_len(), source)
+        };
+        other
+            .iter()
+            .take(rows)
+            .map(move |row| source.iter().map(|i| &row.row_values[*i]).collect())
+    }
+
+    /// Copy the rows that `keep` returns `true` for into a new table.
+    fn copy_rows<'t, F>(&'t self, mut keep: F) -> Self
+    where
+        F: FnMut(usize, &'t [V]) -> bool,
+        K: Clone,
+        V: Clone,
+    {
+        let mut values_vector = Vec::new();
+        for (row, values) in self.iter().enumerate() {
+            if keep(row, values.row_values) {
+                values_vector.extend_from_slice(values.row_values);
+            }
+        }
+        Self {
+            indices_table: self.indices_table.clone(),
+            values_vector,
+        }
+    }
+
+    /// Indices of the rows that have the same values in the `columns` as an earlier row.
+    ///
+    /// Returns `None` if one of the `columns` is not in the table. If no columns are given, every
+    /// row but the first one is a duplicate.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let table = HashTable::from_column_keys_and_rows(
+    ///     ["name", "city"],
+    ///     [["Ann", "Oslo"], ["Bob", "Rome"], ["Ann", "Oslo"], ["Ann", "Rome"]],
+    /// );
+    /// assert_eq!(table.duplicated_rows(&["name", "city"]), Some(vec![2]));
+    /// assert_eq!(table.duplicated_rows(&["name"]), Some(vec![2, 3]));
+    /// assert_eq!(table.duplicated_rows(&["nmae"]), None);
+    /// ```
+    pub fn duplicated_rows<'q, Q, I>(&self, columns: I) -> Option<Vec<usize>>
+    where
+        I: IntoIterator<Item = &'q Q>,
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'q,
+    {
+        let indices: Vec<usize> = columns
+            .into_iter()
+            .map(|column| self.column_index(column))
+            .collect::<Option<_>>()?;
+        let mut seen = HashSet::with_capacity(self.rows_len());
+        Some(
+            self.iter()
+                .enumerate()
+                .filter(|(_, row)| {
+                    !seen.insert(
+                        indices
+                            .iter()
+                            .map(|i| &row.row_values[*i])
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .map(|(row, _)| row)
+                .collect(),
+        )
+    }
+
+    /// Copy the rows into a new table, keeping only the first of the rows with the same values.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let table = HashTable::from_column_keys_and_rows(["x", "y"], [[1, 2], [3, 4], [1, 2]]);
+    /// assert_eq!(table.distinct().rows_len(), 2);
+    /// ```
+    pub fn distinct(&self) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut seen = HashSet::with_capacity(self.rows_len());
+        self.copy_rows(|_, values| seen.insert(values))
+    }
+
+    /// Copy the rows into a new table, keeping only the first of the rows with the same values in
+    /// the `columns`.
+    ///
+    /// Returns `None` if one of the `columns` is not in the table.
+    pub fn distinct_by<'q, Q, I>(&self, columns: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'q Q>,
+        K: Borrow<Q> + Clone,
+        Q: Hash + Eq + ?Sized + 'q,
+        V: Clone,
+    {
+        let duplicated = self.duplicated_rows(columns)?;
+        let mut duplicated = duplicated.iter().peekable();
+        Some(self.copy_rows(|row, _| duplicated.next_if_eq(&&row).is_none()))
+    }
+
+    /// Copy the distinct rows of this table and the `other` table into a new table.
+    ///
+    /// Rows of this table come first, followed by the rows of the `other` table that are not in
+    /// this table. The new table has the column order of this table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tables have different sets of columns, unless one of them has no columns.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let a = HashTable::from_column_keys_and_rows(["x", "y"], [[1, 2], [3, 4]]);
+    /// let b = HashTable::from_column_keys_and_rows(["y", "x"], [[4, 3], [6, 5]]);
+    /// let union = a.union(&b);
+    /// assert_eq!(union.rows_len(), 3);
+    /// assert_eq!(union.get(&"x", 2), Some(&5));
+    /// ```
+    pub fn union(&self, other: &Self) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        if self.columns_len() == 0 {
+            return other.distinct();
+        }
+        let mut result = self.distinct();
+        let mut seen: HashSet<Vec<&V>> = self
+            .iter()
+            .map(|row| row.row_values.iter().collect())
+            .collect();
+        for values in self.other_rows_in_column_order(other) {
+            if !seen.contains(&values) {
+                result
+                    .values_vector
+                    .extend(values.iter().map(|v| (*v).clone()));
+                seen.insert(values);
+            }
+        }
+        result
+    }
+
+    /// Copy the distinct rows of this table that are also in the `other` table into a new table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tables have different sets of columns, unless one of them has no columns.
+    pub fn intersect(&self, other: &Self) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let others: HashSet<Vec<&V>> = self.other_rows_in_column_order(other).collect();
+        let mut seen = HashSet::with_capacity(self.rows_len());
+        self.copy_rows(|_, values| {
+            others.contains(&values.iter().collect::<Vec<_>>()) && seen.insert(values)
+        })
+    }
+
+    /// Copy the distinct rows of this table that are not in the `other` table into a new table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tables have different sets of columns, unless one of them has no columns.
+    pub fn except(&self, other: &Self) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let others: HashSet<Vec<&V>> = self.other_rows_in_column_order(other).collect();
+        let mut seen = HashSet::with_capacity(self.rows_len());
+        self.copy_rows(|_, values| {
+            !others.contains(&values.iter().collect::<Vec<_>>()) && seen.insert(values)
+        })
+    }
+}
@@ -0,0 +1,309 @@
+//! Tables with cheap copies
+
+use std::{borrow::Borrow, iter::FusedIterator, sync::Arc};
+
+use crate::{
+    row::{borrowed::HashTableRowBorrowed, mutable::HashTableMutableBorrowedRow},
+    typedefs::*,
+    HashTable,
+};
+
+/// Number of rows in one shared chunk
+pub const ROWS_PER_CHUNK: usize = 1024;
+
+/// A table whose copies share their values until they are changed.
+///
+/// Values are stored in chunks of [`ROWS_PER_CHUNK`] rows behind [`Arc`]s. Cloning the table is
+/// O(1), and changing a value copies only the chunk that contains it, if the chunk is shared with
+/// another copy. Adding or removing columns changes every row and copies the whole table.
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::{HashTable, table::shared::SharedHashTable};
+/// let original: SharedHashTable<_, _> =
+///     HashTable::from_column_keys_and_rows(["id", "score"], (0..10_000).map(|i| [i, 0])).into();
+/// let mut copy = original.clone();
+/// *copy.get_mut("score", 5000).unwrap() = 100;
+///
+/// assert_eq!(original.get("score", 5000), Some(&0));
+/// assert_eq!(copy.get("score", 5000), Some(&100));
+/// assert_eq!(copy.get("id", 9999), Some(&9999));
+/// ```
+#[derive(Debug)]
+pub struct SharedHashTable<K, V> {
+    pub(crate) indices_table: Arc<HashMap<K, usize>>,
+    pub(crate) chunks: Arc<Vec<Arc<Vec<V>>>>,
+    pub(crate) rows: usize,
+}
+
+impl<K, V> Clone for SharedHashTable<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            indices_table: Arc::clone(&self.indices_table),
+            chunks: Arc::clone(&self.chunks),
+            rows: self.rows,
+        }
+    }
+}
+
+impl<K, V> Default for SharedHashTable<K, V> {
+    fn default() -> Self {
+        HashTable::default().into()
+    }
+}
+
+impl<K, V> SharedHashTable<K, V> {
+    /// Returns the number of columns in this table.
+    #[inline(always)]
+    pub fn columns_len(&self) -> usize {
+        self.indices_table.len()
+    }
+
+    /// Returns the number of rows in this table.
+    #[inline(always)]
+    pub fn rows_len(&self) -> usize {
+        self.rows
+    }
+
+    /// Return an iterator over the column keys.
+    pub fn column_keys(&self) -> Keys<'_, K, usize> {
+        self.indices_table.keys()
+    }
+
+    /// Chunk index and the range of the values of a row in the chunk
+    fn locate(&self, row: usize) -> Option<(usize, std::ops::Range<usize>)> {
+        if row >= self.rows {
+            return None;
+        }
+        let start = (row % ROWS_PER_CHUNK) * self.columns_len();
+        Some((row / ROWS_PER_CHUNK, start..start + self.columns_len()))
+    }
+
+    /// Get a row of the table.
+    ///
+    /// Returns None if `row` is bigger than or equal to the number of rows.
+    pub fn get_row(&self, row: usize) -> Option<HashTableRowBorrowed<'_, K, V>> {
+        let (chunk, range) = self.locate(row)?;
+        Some(HashTableRowBorrowed {
+            indices_table: &self.indices_table,
+            row_values: &self.chunks[chunk][range],
+        })
+    }
+
+    /// Get row with mutable access, copying its chunk if it's shared.
+    ///
+    /// Returns None if `row` is bigger than or equal to the number of rows.
+    pub fn get_row_mut(&mut self, row: usize) -> Option<HashTableMutableBorrowedRow<'_, K, V>>
+    where
+        V: Clone,
+    {
+        let (chunk, range) = self.locate(row)?;
+        let chunk = Arc::make_mut(&mut Arc::make_mut(&mut self.chunks)[chunk]);
+        Some(HashTableMutableBorrowedRow {
+            indices_table: &self.indices_table,
+            values: &mut chunk[range],
+        })
+    }
+
+    /// Returns `true` if the chunk containing the `row` is shared with another copy of the table.
+    ///
+    /// The chunk also counts as shared while the list of chunks itself is shared, e.g. right after
+    /// the table is cloned, as changing the row would copy the chunk in both cases.
+    pub fn is_row_shared(&self, row: usize) -> bool {
+        self.locate(row).is_some_and(|(chunk, _)| {
+            Arc::strong_count(&self.chunks) > 1 || Arc::strong_count(&self.chunks[chunk]) > 1
+        })
+    }
+
+    /// Iterate over the rows of the table.
+    pub fn iter(&self) -> SharedHashTableIter<'_, K, V> {
+        SharedHashTableIter {
+            table: self,
+            rows: 0..self.rows,
+        }
+    }
+
+    /// Copy the values into a regular table.
+    pub fn to_table(&self) -> HashTable<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut values_vector = Vec::with_capacity(self.rows * self.columns_len());
+        for chunk in self.chunks.iter() {
+            values_vector.extend_from_slice(chunk);
+        }
+        HashTable {
+            indices_table: (*self.indices_table).clone(),
+            values_vector,
+        }
+    }
+}
+
+impl<K, V> SharedHashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Create a table with the provided columns and no rows.
+    pub fn with_columns(columns: impl IntoIterator<Item = K>) -> Self {
+        HashTable::with_columns(columns).into()
+    }
+
+    /// Get a reference to a value in the table.
+    pub fn get<Q>(&self, column: &Q, row: usize) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_row(row)?.get(column)
+    }
+
+    /// Get a mutable reference to a value in the table, copying its chunk if it's shared.
+    pub fn get_mut<Q>(&mut self, column: &Q, row: usize) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        let index = *self.indices_table.get(column)?;
+        let (chunk, range) = self.locate(row)?;
+        let chunk = Arc::make_mut(&mut Arc::make_mut(&mut self.chunks)[chunk]);
+        Some(&mut chunk[range.start + index])
+    }
+
+    /// Add a row to the table using a generator function that returns the value from the column
+    /// key.
+    ///
+    /// Only the last chunk is copied if it's shared.
+    pub fn push_row_with<F>(&mut self, mut row_generator: F)
+    where
+        F: FnMut(&K) -> V,
+        V: Clone,
+    {
+        if self.columns_len() == 0 {
+            return;
+        }
+        let mut keys = self.indices_table.iter().collect::<Vec<_>>();
+        keys.sort_by_key(|(_, i)| *i);
+        let chunks = Arc::make_mut(&mut self.chunks);
+        if self.rows % ROWS_PER_CHUNK == 0 {
+            chunks.push(Arc::new(Vec::with_capacity(
+                ROWS_PER_CHUNK * self.indices_table.len(),
+            )));
+        }
+        let last = Arc::make_mut(chunks.last_mut().expect("A chunk was just added"));
+        last.extend(keys.into_iter().map(|(k, _)| row_generator(k)));
+        self.rows += 1;
+    }
+
+    /// Apply a change of columns to a copy of the table, copying every value.
+    fn modify_columns<R>(&mut self, f: impl FnOnce(&mut HashTable<K, V>) -> R) -> R
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut table = self.to_table();
+        let result = f(&mut table);
+        *self = table.into();
+        result
+    }
+
+    /// Add a column with values provided through an iterator, copying the whole table.
+    ///
+    /// See [`HashTable::insert_column`] for details.
+    pub fn insert_column<I>(&mut self, column: K, values: I)
+    where
+        I: IntoIterator<Item = V>,
+        K: Clone,
+        V: Clone,
+    {
+        self.modify_columns(|table| table.insert_column(column, values))
+    }
+
+    /// Remove a column and return its values, copying the rest of the table.
+    pub fn remove_column<Q>(&mut self, column: &Q) -> Option<Vec<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        K: Clone,
+        V: Clone,
+    {
+        if !self.indices_table.contains_key(column) {
+            return None;
+        }
+        self.modify_columns(|table| table.remove_column(column))
+            .map(|column| column.into_values())
+    }
+}
+
+impl<K, V> From<HashTable<K, V>> for SharedHashTable<K, V> {
+    fn from(table: HashTable<K, V>) -> Self {
+        let rows = table.rows_len();
+        let HashTable {
+            indices_table,
+            values_vector,
+        } = table;
+        let chunk_len = (ROWS_PER_CHUNK * indices_table.len()).max(1);
+        let mut chunks = Vec::with_capacity(values_vector.len().div_ceil(chunk_len));
+        let mut values = values_vector.into_iter().peekable();
+        while values.peek().is_some() {
+            let mut chunk = Vec::with_capacity(chunk_len);
+            chunk.extend(values.by_ref().take(chunk_len));
+            chunks.push(Arc::new(chunk));
+        }
+        Self {
+            indices_table: Arc::new(indices_table),
+            chunks: Arc::new(chunks),
+            rows,
+        }
+    }
+}
+
+impl<'t, K, V> IntoIterator for &'t SharedHashTable<K, V> {
+    type Item = HashTableRowBorrowed<'t, K, V>;
+    type IntoIter = SharedHashTableIter<'t, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the rows of a [`SharedHashTable`].
+///
+/// Returned by [`SharedHashTable::iter`].
+#[derive(Debug)]
+pub struct SharedHashTableIter<'t, K, V> {
+    table: &'t SharedHashTable<K, V>,
+    rows: std::ops::Range<usize>,
+}
+
+impl<K, V> Clone for SharedHashTableIter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table,
+            rows: self.rows.clone(),
+        }
+    }
+}
+
+impl<'t, K, V> Iterator for SharedHashTableIter<'t, K, V> {
+    type Item = HashTableRowBorrowed<'t, K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.table.get_row(self.rows.next()?)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for SharedHashTableIter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.table.get_row(self.rows.next_back()?)
+    }
+}
+
+impl<K, V> FusedIterator for SharedHashTableIter<'_, K, V> {}
+
+impl<K, V> ExactSizeIterator for SharedHashTableIter<'_, K, V> {}
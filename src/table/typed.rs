@@ -0,0 +1,494 @@
+//! Tables with a fixed set of columns known at compile time
+
+use std::{
+    iter::FusedIterator,
+    ops::{Index, IndexMut},
+};
+
+use crate::{typedefs::*, HashTable};
+
+/// A column of a [`TypedHashTable`], usually a field-less enum.
+///
+/// [`Self::ALL`] must list every column once, in the order of [`Self::index`], so that
+/// `K::ALL[key.index()] == key`. Tables assume it and check it in debug builds.
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::table::typed::ColumnKey;
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Body {
+///     Mass,
+///     Velocity,
+/// }
+///
+/// impl ColumnKey for Body {
+///     const ALL: &'static [Self] = &[Body::Mass, Body::Velocity];
+///
+///     fn index(self) -> usize {
+///         self as usize
+///     }
+/// }
+/// ```
+pub trait ColumnKey: Copy + Eq + 'static {
+    /// Every column, ordered by [`Self::index`]
+    const ALL: &'static [Self];
+
+    /// Index of the column, its position in [`Self::ALL`]
+    fn index(self) -> usize;
+}
+
+/// Check in debug builds that a [`ColumnKey`] type upholds the invariant of the trait.
+fn debug_assert_column_keys<K: ColumnKey>() {
+    debug_assert!(
+        K::ALL.iter().enumerate().all(|(i, key)| key.index() == i),
+        "ColumnKey::index doesn't match the position in ColumnKey::ALL"
+    );
+}
+
+/// A table whose columns are the values of a [`ColumnKey`] type.
+///
+/// Every row has a value in every column, and looking up a column is an array index instead of a
+/// hash map lookup.
+///
+/// ## Example
+/// ```
+/// # use hash_table_datastruct::table::typed::{ColumnKey, TypedHashTable};
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Body {
+///     Mass,
+///     Velocity,
+/// }
+///
+/// impl ColumnKey for Body {
+///     const ALL: &'static [Self] = &[Body::Mass, Body::Velocity];
+///
+///     fn index(self) -> usize {
+///         self as usize
+///     }
+/// }
+///
+/// let mut bodies = TypedHashTable::new();
+/// bodies.push_row_with(|column| match column {
+///     Body::Mass => 2.0,
+///     Body::Velocity => 3.0,
+/// });
+/// for mut body in bodies.iter_mut() {
+///     let [mass, velocity] = body.get_many_mut([Body::Mass, Body::Velocity]).unwrap();
+///     *velocity /= *mass;
+/// }
+/// assert_eq!(bodies[(Body::Velocity, 0)], 1.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedHashTable<K, V> {
+    values: Vec<V>,
+    columns: std::marker::PhantomData<K>,
+}
+
+impl<K: ColumnKey, V> Default for TypedHashTable<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: ColumnKey, V> TypedHashTable<K, V> {
+    /// Create a table without rows.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a table with space reserved for `rows` rows.
+    pub fn with_capacity(rows: usize) -> Self {
+        debug_assert_column_keys::<K>();
+        Self {
+            values: Vec::with_capacity(rows * K::ALL.len()),
+            columns: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the number of columns in this table.
+    #[inline(always)]
+    pub fn columns_len(&self) -> usize {
+        K::ALL.len()
+    }
+
+    /// Returns the number of rows in this table.
+    #[inline(always)]
+    pub fn rows_len(&self) -> usize {
+        self.values.len().checked_div(K::ALL.len()).unwrap_or(0)
+    }
+
+    /// Get the column keys of this table ordered by their column index.
+    pub fn column_keys(&self) -> &'static [K] {
+        K::ALL
+    }
+
+    /// Get an element from the table.
+    ///
+    /// Will return None if `row` is out of range.
+    #[inline]
+    pub fn get(&self, column: K, row: usize) -> Option<&V> {
+        self.get_row(row).map(|row| row.get(column))
+    }
+
+    /// Get an element from the table with mutable access.
+    ///
+    /// Will return None if `row` is out of range.
+    #[inline]
+    pub fn get_mut(&mut self, column: K, row: usize) -> Option<&mut V> {
+        self.get_row_mut(row).map(|row| row.into_mut(column))
+    }
+
+    /// Get a row of the table.
+    ///
+    /// Returns None if `row` is bigger than or equal to the number of rows.
+    pub fn get_row(&self, row: usize) -> Option<TypedRow<'_, K, V>> {
+        let start = row.checked_mul(K::ALL.len())?;
+        Some(TypedRow {
+            values: self.values.get(start..start + K::ALL.len())?,
+            columns: std::marker::PhantomData,
+        })
+    }
+
+    /// Get row with mutable access.
+    ///
+    /// Returns None if `row` is bigger than or equal to the number of rows.
+    pub fn get_row_mut(&mut self, row: usize) -> Option<TypedRowMut<'_, K, V>> {
+        let start = row.checked_mul(K::ALL.len())?;
+        Some(TypedRowMut {
+            values: self.values.get_mut(start..start + K::ALL.len())?,
+            columns: std::marker::PhantomData,
+        })
+    }
+
+    /// Iterate over the values of a column.
+    pub fn get_column(&self, column: K) -> impl ExactSizeIterator<Item = &V> + '_ {
+        self.iter().map(move |row| row.get(column))
+    }
+
+    /// Iterate over the rows of the table.
+    pub fn iter(&self) -> TypedHashTableIter<'_, K, V> {
+        TypedHashTableIter {
+            rows: self.values.chunks_exact(K::ALL.len().max(1)),
+            columns: std::marker::PhantomData,
+        }
+    }
+
+    /// Iterate over the rows of the table with mutable access.
+    pub fn iter_mut(&mut self) -> TypedHashTableIterMut<'_, K, V> {
+        TypedHashTableIterMut {
+            rows: self.values.chunks_exact_mut(K::ALL.len().max(1)),
+            columns: std::marker::PhantomData,
+        }
+    }
+
+    /// Add a row to the table using a generator function that returns the value from the column
+    /// key.
+    pub fn push_row_with<F>(&mut self, row_generator: F)
+    where
+        F: FnMut(K) -> V,
+    {
+        self.values
+            .extend(K::ALL.iter().copied().map(row_generator));
+    }
+
+    /// Remove a row and take ownership of its values, in column-index order.
+    pub fn remove_row(&mut self, row: usize) -> Option<Vec<V>> {
+        if row >= self.rows_len() {
+            return None;
+        }
+        let start = row * K::ALL.len();
+        Some(self.values.drain(start..start + K::ALL.len()).collect())
+    }
+
+    /// Keep only the rows for which the `predicate` returns `true`.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(TypedRow<'_, K, V>) -> bool,
+    {
+        let keep: Vec<bool> = self.iter().map(&mut predicate).collect();
+        let mut i = 0;
+        self.values.retain(|_| {
+            let keep_value = keep[i / K::ALL.len()];
+            i += 1;
+            keep_value
+        });
+    }
+}
+
+impl<K, V> From<TypedHashTable<K, V>> for HashTable<K, V>
+where
+    K: ColumnKey + Hash,
+{
+    fn from(table: TypedHashTable<K, V>) -> Self {
+        HashTable {
+            indices_table: K::ALL.iter().map(|key| (*key, key.index())).collect(),
+            values_vector: table.values,
+        }
+    }
+}
+
+/// Fails with the unchanged table if it doesn't have exactly the columns of the [`ColumnKey`]
+/// type.
+impl<K, V> TryFrom<HashTable<K, V>> for TypedHashTable<K, V>
+where
+    K: ColumnKey + Hash,
+{
+    type Error = HashTable<K, V>;
+
+    fn try_from(mut table: HashTable<K, V>) -> Result<Self, Self::Error> {
+        debug_assert_column_keys::<K>();
+        if table.columns_len() != K::ALL.len()
+            || K::ALL
+                .iter()
+                .any(|key| !table.indices_table.contains_key(key))
+        {
+            return Err(table);
+        }
+        let order: Vec<usize> = K::ALL.iter().map(|key| table.indices_table[key]).collect();
+        let mut values: Vec<Option<V>> = table.values_vector.drain(..).map(Some).collect();
+        let values = values
+            .chunks_exact_mut(K::ALL.len().max(1))
+            .flat_map(|row| {
+                order
+                    .iter()
+                    .map(|i| row[*i].take().expect("Column indices are unique"))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Ok(Self {
+            values,
+            columns: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<K: ColumnKey, V> Index<(K, usize)> for TypedHashTable<K, V> {
+    type Output = V;
+
+    fn index(&self, (column, row): (K, usize)) -> &Self::Output {
+        self.get(column, row).unwrap()
+    }
+}
+
+impl<K: ColumnKey, V> IndexMut<(K, usize)> for TypedHashTable<K, V> {
+    fn index_mut(&mut self, (column, row): (K, usize)) -> &mut Self::Output {
+        self.get_mut(column, row).unwrap()
+    }
+}
+
+impl<'t, K: ColumnKey, V> IntoIterator for &'t TypedHashTable<K, V> {
+    type Item = TypedRow<'t, K, V>;
+    type IntoIter = TypedHashTableIter<'t, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'t, K: ColumnKey, V> IntoIterator for &'t mut TypedHashTable<K, V> {
+    type Item = TypedRowMut<'t, K, V>;
+    type IntoIter = TypedHashTableIterMut<'t, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Row-wise iterator over a [`TypedHashTable`]
+///
+/// Returned by [`TypedHashTable::iter`]
+#[derive(Debug, Clone)]
+pub struct TypedHashTableIter<'t, K, V> {
+    rows: std::slice::ChunksExact<'t, V>,
+    columns: std::marker::PhantomData<K>,
+}
+
+impl<'t, K, V> Iterator for TypedHashTableIter<'t, K, V> {
+    type Item = TypedRow<'t, K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|values| TypedRow {
+            values,
+            columns: std::marker::PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for TypedHashTableIter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back().map(|values| TypedRow {
+            values,
+            columns: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<K, V> FusedIterator for TypedHashTableIter<'_, K, V> {}
+
+impl<K, V> ExactSizeIterator for TypedHashTableIter<'_, K, V> {}
+
+/// Row-wise iterator over a [`TypedHashTable`] with mutable access to the values
+///
+/// Returned by [`TypedHashTable::iter_mut`]
+#[derive(Debug)]
+pub struct TypedHashTableIterMut<'t, K, V> {
+    rows: std::slice::ChunksExactMut<'t, V>,
+    columns: std::marker::PhantomData<K>,
+}
+
+impl<'t, K, V> Iterator for TypedHashTableIterMut<'t, K, V> {
+    type Item = TypedRowMut<'t, K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|values| TypedRowMut {
+            values,
+            columns: std::marker::PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for TypedHashTableIterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back().map(|values| TypedRowMut {
+            values,
+            columns: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<K, V> FusedIterator for TypedHashTableIterMut<'_, K, V> {}
+
+impl<K, V> ExactSizeIterator for TypedHashTableIterMut<'_, K, V> {}
+
+/// A row of a [`TypedHashTable`] that gives a borrowed access to its values
+#[derive(Debug)]
+pub struct TypedRow<'t, K, V> {
+    values: &'t [V],
+    columns: std::marker::PhantomData<K>,
+}
+
+impl<K, V> Clone for TypedRow<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for TypedRow<'_, K, V> {}
+
+impl<'t, K: ColumnKey, V> TypedRow<'t, K, V> {
+    /// Get an element of the row in the requested `column`
+    pub fn get(&self, column: K) -> &'t V {
+        &self.values[column.index()]
+    }
+
+    /// Get elements of the row in several columns at once
+    pub fn get_many<const N: usize>(&self, columns: [K; N]) -> [&'t V; N] {
+        columns.map(|column| self.get(column))
+    }
+
+    /// Get the element of the row in the column with the index `index`
+    pub fn get_index(&self, index: usize) -> Option<&'t V> {
+        self.values.get(index)
+    }
+
+    /// Return an iterator over the keys of the columns of the table
+    pub fn columns_keys(&self) -> &'static [K] {
+        K::ALL
+    }
+
+    /// Return an amount of columns in the row
+    pub fn columns_len(&self) -> usize {
+        K::ALL.len()
+    }
+
+    /// Return an iterator over the values of the row in column-index order
+    pub fn values(&self) -> std::slice::Iter<'t, V> {
+        self.values.iter()
+    }
+
+    /// Clone the values of the row in column-index order
+    pub fn to_vec(&self) -> Vec<V>
+    where
+        V: Clone,
+    {
+        self.values.to_vec()
+    }
+
+    /// Clone the row into a map from column keys to values
+    pub fn to_hashmap(&self) -> HashMap<K, V>
+    where
+        K: Hash,
+        V: Clone,
+    {
+        self.into_iter().map(|(k, v)| (k, v.clone())).collect()
+    }
+}
+
+impl<'t, K: ColumnKey, V> IntoIterator for TypedRow<'t, K, V> {
+    type Item = (K, &'t V);
+    type IntoIter =
+        std::iter::Zip<std::iter::Copied<std::slice::Iter<'static, K>>, std::slice::Iter<'t, V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        K::ALL.iter().copied().zip(self.values)
+    }
+}
+
+impl<K, V: PartialEq> PartialEq for TypedRow<'_, K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<K, V: Eq> Eq for TypedRow<'_, K, V> {}
+
+/// Row of a [`TypedHashTable`] with mutable access to the values.
+#[derive(Debug)]
+pub struct TypedRowMut<'t, K, V> {
+    values: &'t mut [V],
+    columns: std::marker::PhantomData<K>,
+}
+
+impl<'t, K: ColumnKey, V> TypedRowMut<'t, K, V> {
+    /// Get an element of this row in the requested `column`.
+    pub fn get(&mut self, column: K) -> &mut V {
+        &mut self.values[column.index()]
+    }
+
+    /// Convert the row into a mutable reference to an element in the requested `column`.
+    pub fn into_mut(self, column: K) -> &'t mut V {
+        &mut self.values[column.index()]
+    }
+
+    /// Get mutable references to elements of this row in several columns at once.
+    ///
+    /// Returns None if the same column is requested more than once.
+    pub fn get_many_mut<const N: usize>(&mut self, columns: [K; N]) -> Option<[&mut V; N]> {
+        self.values
+            .get_disjoint_mut(columns.map(|column| column.index()))
+            .ok()
+    }
+
+    /// Return an iterator over the values of the row in column-index order
+    pub fn values_mut(&mut self) -> std::slice::IterMut<'_, V> {
+        self.values.iter_mut()
+    }
+}
+
+impl<'t, K: ColumnKey, V> IntoIterator for TypedRowMut<'t, K, V> {
+    type Item = (K, &'t mut V);
+    type IntoIter =
+        std::iter::Zip<std::iter::Copied<std::slice::Iter<'static, K>>, std::slice::IterMut<'t, V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        K::ALL.iter().copied().zip(self.values)
+    }
+}
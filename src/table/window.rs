@@ -0,0 +1,222 @@
+//! Rolling windows and cumulative operations over the rows of a column
+//!
+//! Every operation returns one value per row, so the result can be added to the table with
+//! [`HashTable::insert_column`] or written over the column with
+//! [`HashTable::replace_column_values`]. Rows without a result, such as the first row of
+//! [`HashTable::diff_column`], are `None`.
+//!
+//! ## Example
+//! ```
+//! # use hash_table_datastruct::HashTable;
+//! let mut prices = HashTable::from_column_keys_and_rows(
+//!     ["time", "price"],
+//!     [[1.0, 10.0], [2.0, 12.0], [3.0, 11.0], [4.0, 15.0]],
+//! );
+//! let average = prices
+//!     .rolling(&"price", 2)
+//!     .unwrap()
+//!     .map(|window| window.iter().sum::<f64>() / 2.0);
+//! prices.insert_column("average", average.into_iter().map(|v| v.unwrap_or(f64::NAN)));
+//! assert_eq!(prices.get(&"average", 3), Some(&13.0));
+//!
+//! let total = prices.cumsum(&"price").unwrap();
+//! prices.replace_column_values(&"price", total.into_iter().map(Option::unwrap));
+//! assert_eq!(prices.get(&"price", 3), Some(&48.0));
+//! ```
+
+use std::borrow::Borrow;
+
+use crate::{stats::Numeric, typedefs::*, HashTable};
+
+/// Windows of consecutive values of a column.
+///
+/// Returned by [`HashTable::rolling`].
+#[derive(Debug, Clone)]
+pub struct Rolling<V> {
+    values: Vec<V>,
+    window: usize,
+}
+
+impl<V> Rolling<V> {
+    /// Returns the number of values in each window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Compute a value for each row from the window of values that ends at the row.
+    ///
+    /// Rows before the first full window are `None`.
+    pub fn map<T, F>(self, mut f: F) -> Vec<Option<T>>
+    where
+        F: FnMut(&[V]) -> T,
+    {
+        let incomplete = self.values.len().min(self.window - 1);
+        std::iter::repeat_with(|| None)
+            .take(incomplete)
+            .chain(
+                self.values
+                    .windows(self.window)
+                    .map(|window| Some(f(window))),
+            )
+            .collect()
+    }
+}
+
+impl<K, V> HashTable<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Copy the values of a column in row order.
+    fn column_vec<Q>(&self, column: &Q) -> Option<Vec<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        let index = self.column_index(column)?;
+        Some(
+            self.values_vector
+                .chunks_exact(self.columns_len())
+                .map(|row| row[index].clone())
+                .collect(),
+        )
+    }
+
+    /// Get windows of `window` consecutive values of a column.
+    ///
+    /// Returns `None` if there is no such column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    pub fn rolling<Q>(&self, column: &Q, window: usize) -> Option<Rolling<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        assert!(window > 0, "Window must contain at least one value");
+        Some(Rolling {
+            values: self.column_vec(column)?,
+            window,
+        })
+    }
+
+    /// Compute the sum of the values of a column up to each row.
+    ///
+    /// Rows from the first one where the sum of an integer column overflows are `None`. Returns
+    /// `None` if there is no such column.
+    pub fn cumsum<Q>(&self, column: &Q) -> Option<Vec<Option<V>>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        Some(accumulate(self.column_vec(column)?, V::checked_add))
+    }
+
+    /// Compute the product of the values of a column up to each row.
+    ///
+    /// Rows from the first one where the product of an integer column overflows are `None`.
+    /// Returns `None` if there is no such column.
+    pub fn cumprod<Q>(&self, column: &Q) -> Option<Vec<Option<V>>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        Some(accumulate(self.column_vec(column)?, V::checked_mul))
+    }
+
+    /// Compute the difference between the value of a column in each row and in the previous row.
+    ///
+    /// The first row has no previous row and is `None`, as are rows where the difference of an
+    /// integer column overflows, such as a decrease in an unsigned column. Returns `None` if
+    /// there is no such column.
+    pub fn diff_column<Q>(&self, column: &Q) -> Option<Vec<Option<V>>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        let differences = pairwise(self.column_vec(column)?, |previous, value| {
+            value.checked_sub(previous)
+        });
+        Some(differences.into_iter().map(Option::flatten).collect())
+    }
+
+    /// Compute the relative change between the value of a column in each row and in the previous
+    /// row.
+    ///
+    /// The first row has no previous row and is `None`. Returns `None` if there is no such column.
+    pub fn pct_change<Q>(&self, column: &Q) -> Option<Vec<Option<f64>>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Numeric,
+    {
+        Some(pairwise(self.column_vec(column)?, |previous, value| {
+            value.to_f64() / previous.to_f64() - 1.0
+        }))
+    }
+
+    /// Move the values of a column by `n` rows, towards later rows if `n` is positive and towards
+    /// earlier rows if it's negative.
+    ///
+    /// Rows left without a value get the `fill` value. Returns `None` if there is no such column.
+    ///
+    /// ## Example
+    /// ```
+    /// # use hash_table_datastruct::HashTable;
+    /// let table = HashTable::from_column_keys_and_rows(["x"], [[1], [2], [3]]);
+    /// assert_eq!(table.shift(&"x", 1, 0), Some(vec![0, 1, 2]));
+    /// assert_eq!(table.shift(&"x", -2, 0), Some(vec![3, 0, 0]));
+    /// ```
+    pub fn shift<Q>(&self, column: &Q, n: isize, fill: V) -> Option<Vec<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        let values = self.column_vec(column)?;
+        let len = values.len();
+        let offset = n.unsigned_abs().min(len);
+        Some(if n >= 0 {
+            std::iter::repeat_n(fill, offset)
+                .chain(values.into_iter().take(len - offset))
+                .collect()
+        } else {
+            values
+                .into_iter()
+                .skip(offset)
+                .chain(std::iter::repeat_n(fill, offset))
+                .collect()
+        })
+    }
+}
+
+/// Combine each value with the result for the previous value, until `f` fails
+fn accumulate<V: Copy>(values: Vec<V>, f: impl Fn(V, V) -> Option<V>) -> Vec<Option<V>> {
+    let mut result = None;
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            result = if i == 0 {
+                Some(value)
+            } else {
+                result.and_then(|result| f(result, value))
+            };
+            result
+        })
+        .collect()
+}
+
+/// Combine each value with the previous value
+fn pairwise<V: Copy, T>(values: Vec<V>, f: impl Fn(V, V) -> T) -> Vec<Option<T>> {
+    let first = values.first().map(|_| None);
+    first
+        .into_iter()
+        .chain(values.windows(2).map(|pair| Some(f(pair[0], pair[1]))))
+        .collect()
+}
@@ -0,0 +1,64 @@
+use arrow_array::Array;
+use arrow_schema::DataType;
+
+use crate::{value::Value, HashTable};
+
+#[test]
+fn value_columns_use_natural_types() {
+    let table = HashTable::from_column_keys_and_rows(
+        ["i", "f", "s", "n"].map(str::to_owned),
+        [
+            [Value::Int(1), Value::Int(2), Value::from("a"), Value::Null],
+            [
+                Value::Null,
+                Value::Float(0.5),
+                Value::from("b"),
+                Value::Null,
+            ],
+        ],
+    );
+    let batch = table.to_record_batch().unwrap();
+    let types: Vec<&DataType> = batch
+        .schema_ref()
+        .fields()
+        .iter()
+        .map(|f| f.data_type())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            &DataType::Int64,
+            &DataType::Float64,
+            &DataType::Utf8,
+            &DataType::Null
+        ]
+    );
+    assert_eq!(batch.column(0).null_count(), 1);
+
+    let restored = HashTable::<String, Value>::from_record_batch(&batch).unwrap();
+    assert_eq!(
+        restored.column_keys_in_order(),
+        table.column_keys_in_order()
+    );
+    assert_eq!(restored.get("i", 1), Some(&Value::Null));
+    assert_eq!(restored.get("f", 0), Some(&Value::Float(2.0)));
+    assert_eq!(restored.get("s", 1), Some(&Value::from("b")));
+}
+
+#[test]
+fn mixed_value_column_is_an_error() {
+    let table = HashTable::from_column_keys_and_rows(
+        ["x".to_owned()],
+        [[Value::Int(1)], [Value::from("a")]],
+    );
+    assert!(table.to_record_batch().is_err());
+}
+
+#[test]
+fn nulls_require_option_values() {
+    let table = HashTable::from_column_keys_and_rows(["x".to_owned()], [[Some(1_i32)], [None]]);
+    let batch = table.to_record_batch().unwrap();
+    assert!(HashTable::<String, i32>::from_record_batch(&batch).is_err());
+    let restored = HashTable::<String, Option<i32>>::from_record_batch(&batch).unwrap();
+    assert_eq!(restored.get("x", 1), Some(&None));
+}
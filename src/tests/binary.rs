@@ -0,0 +1,105 @@
+use std::io::ErrorKind;
+
+use crate::{binary::BinaryRowReader, value::Value, HashTable};
+
+fn sample() -> HashTable<String, Value> {
+    HashTable::from_column_keys_and_rows(
+        ["name", "age", "score", "active"].map(str::to_owned),
+        [
+            [
+                Value::from("Ann"),
+                Value::Int(31),
+                Value::Float(7.5),
+                Value::Bool(true),
+            ],
+            [
+                Value::from("Bob"),
+                Value::Null,
+                Value::Float(-1.0),
+                Value::Bool(false),
+            ],
+        ],
+    )
+}
+
+#[test]
+fn round_trip_keeps_columns_and_values() {
+    let table = sample();
+    let mut buffer = Vec::new();
+    table.write_binary(&mut buffer).unwrap();
+    let restored = HashTable::<String, Value>::read_binary(&mut buffer.as_slice()).unwrap();
+
+    assert_eq!(
+        restored.column_keys_in_order(),
+        table.column_keys_in_order()
+    );
+    assert_eq!(restored.values_vector, table.values_vector);
+}
+
+#[test]
+fn row_reader_streams_rows() {
+    let mut buffer = Vec::new();
+    sample().write_binary(&mut buffer).unwrap();
+    let mut rows = BinaryRowReader::<_, String, Value>::new(buffer.as_slice()).unwrap();
+
+    assert_eq!(rows.column_keys(), ["name", "age", "score", "active"]);
+    assert_eq!(rows.remaining_rows(), 2);
+    let first = rows.next().unwrap().unwrap();
+    assert_eq!(first[0], Value::from("Ann"));
+    let second = rows.next().unwrap().unwrap();
+    assert_eq!(second[1], Value::Null);
+    assert!(rows.next().is_none());
+    assert!(rows.into_inner().is_empty());
+}
+
+#[test]
+fn invalid_input_is_an_error() {
+    let error = HashTable::<String, i32>::read_binary(&mut &b"JSON{}"[..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let mut buffer = Vec::new();
+    sample().write_binary(&mut buffer).unwrap();
+    buffer.truncate(buffer.len() - 1);
+    let error = HashTable::<String, Value>::read_binary(&mut buffer.as_slice()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+    let mut rows = BinaryRowReader::<_, String, Value>::new(buffer.as_slice()).unwrap();
+    assert!(rows.next().unwrap().is_ok());
+    assert!(rows.next().unwrap().is_err());
+    assert!(rows.next().is_none());
+}
+
+#[test]
+fn duplicate_keys_are_rejected() {
+    let mut buffer = Vec::new();
+    HashTable::from_column_keys_and_rows(["a".to_owned(), "b".to_owned()], [[1_u8, 2]])
+        .write_binary(&mut buffer)
+        .unwrap();
+    let b = buffer.iter().rposition(|byte| *byte == b'b').unwrap();
+    buffer[b] = b'a';
+    let error = HashTable::<String, u8>::read_binary(&mut buffer.as_slice()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn writes_are_buffered() {
+    struct CountingWriter(Vec<u8>, usize);
+
+    impl std::io::Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.1 += 1;
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let table = HashTable::from_column_keys_and_rows(["a".to_owned()], (0..1000_u32).map(|i| [i]));
+    let mut writer = CountingWriter(Vec::new(), 0);
+    table.write_binary(&mut writer).unwrap();
+    assert_eq!(writer.1, 1);
+    let restored = HashTable::<String, u32>::read_binary(&mut writer.0.as_slice()).unwrap();
+    assert_eq!(restored.get("a", 999), Some(&999));
+}
@@ -0,0 +1,88 @@
+use crate::HashTable;
+
+fn sample_table() -> HashTable<&'static str, i32> {
+    HashTable::from_column_keys_and_rows(["a", "b"], [[1, 2], [3, 4], [5, 6]])
+}
+
+#[test]
+fn insert_column_keeps_rows_aligned() {
+    let mut table = sample_table();
+    table.insert_column("c", [10, 20, 30]);
+    assert_eq!(table.columns_len(), 3);
+    assert_eq!(table.rows_len(), 3);
+    for (row, (a, c)) in [(1, 10), (3, 20), (5, 30)].into_iter().enumerate() {
+        assert_eq!(table[(&"a", row)], a);
+        assert_eq!(table[(&"c", row)], c);
+    }
+}
+
+#[test]
+fn remove_row_of_non_square_table() {
+    let mut table = sample_table();
+    let row = table.remove_row(1).unwrap();
+    assert_eq!((row.get("a"), row.get("b")), (Some(&3), Some(&4)));
+    drop(row);
+    assert_eq!(table.rows_len(), 2);
+    assert_eq!((table[(&"a", 1)], table[(&"b", 1)]), (5, 6));
+
+    let mut table = HashTable::from_column_keys_and_rows(["a", "b", "c"], [[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(table.remove_row(1).unwrap().get("c"), Some(&6));
+    assert_eq!(table[(&"c", 0)], 3);
+}
+
+#[test]
+fn insert_columns_into_empty_table() {
+    let mut table = HashTable::default();
+    table.insert_columns([("x", vec![1, 2]), ("y", vec![3, 4])]);
+    assert_eq!(table.rows_len(), 2);
+    assert_eq!(table.get(&"x", 1), Some(&2));
+    assert_eq!(table.get(&"y", 0), Some(&3));
+}
+
+#[test]
+fn insert_column_with_uses_row_values() {
+    let mut table = sample_table();
+    table.insert_column_with("sum", |row| row.get(&"a").unwrap() + row.get(&"b").unwrap());
+    assert_eq!(table.get(&"sum", 2), Some(&11));
+}
+
+#[test]
+#[should_panic]
+fn insert_column_length_mismatch() {
+    sample_table().insert_column("c", [1, 2]);
+}
+
+#[test]
+fn failed_insert_columns_leaves_table_unchanged() {
+    let mut table = sample_table();
+    for columns in [
+        vec![("c", vec![7, 8, 9]), ("d", vec![1])],
+        vec![("c", vec![7, 8, 9]), ("c", vec![7, 8, 9])],
+        vec![("c", vec![7, 8, 9]), ("a", vec![7, 8, 9])],
+    ] {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            table.insert_columns(columns)
+        }));
+        assert!(result.is_err());
+        assert_eq!(table.column_keys_in_order(), [&"a", &"b"]);
+        assert_eq!(table.get(&"b", 2), Some(&6));
+    }
+}
+
+#[test]
+fn remove_columns_returns_requested_order() {
+    let mut table = sample_table();
+    table.insert_column("c", [7, 8, 9]);
+    let removed = table.remove_columns([&"c", &"a", &"missing"]);
+    let removed: Vec<_> = removed.into_iter().map(|c| c.into_pair()).collect();
+    assert_eq!(removed, [("c", vec![7, 8, 9]), ("a", vec![1, 3, 5])]);
+    assert_eq!(table.columns_len(), 1);
+    assert_eq!(table.get(&"b", 2), Some(&6));
+}
+
+#[test]
+fn from_column_iter_builds_rows() {
+    let table = HashTable::from_column_iter([("a", vec![1, 2]), ("b", vec![3, 4])]);
+    assert_eq!(table.get(&"a", 1), Some(&2));
+    assert_eq!(table.get(&"b", 1), Some(&4));
+}
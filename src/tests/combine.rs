@@ -0,0 +1,78 @@
+use super::table;
+use crate::HashTable;
+
+#[test]
+fn append_table_matches_columns_by_key() {
+    let mut first = table(["a", "b"], &[[1, 2]]);
+    first.append_table(table(["b", "a"], &[[4, 3], [6, 5]]));
+    assert_eq!(first.rows_len(), 3);
+    for (row, (a, b)) in [(1, 2), (3, 4), (5, 6)].into_iter().enumerate() {
+        assert_eq!(first.get(&"a", row), Some(&a));
+        assert_eq!(first.get(&"b", row), Some(&b));
+    }
+}
+
+#[test]
+fn append_table_to_empty_table() {
+    let mut empty = HashTable::default();
+    empty.append_table(table(["a", "b"], &[[1, 2]]));
+    assert_eq!(empty.column_keys_in_order(), [&"a", &"b"]);
+    assert_eq!(empty.rows_len(), 1);
+}
+
+#[test]
+#[should_panic]
+fn append_table_with_other_columns() {
+    table(["a", "b"], &[[1, 2]]).append_table(table(["a", "c"], &[[3, 4]]));
+}
+
+#[test]
+fn append_table_union_fills_missing_columns() {
+    let mut first = table(["a", "b"], &[[1, 2]]);
+    first.append_table_union(table(["c", "a"], &[[30, 3]]), |_| -1);
+    assert_eq!(first.column_keys_in_order(), [&"a", &"b", &"c"]);
+    assert_eq!(first.get_row(0).unwrap().get(&"c"), Some(&-1));
+    assert_eq!(first.get(&"a", 1), Some(&3));
+    assert_eq!(first.get(&"b", 1), Some(&-1));
+    assert_eq!(first.get(&"c", 1), Some(&30));
+}
+
+#[test]
+fn concat_tables() {
+    let combined = HashTable::concat([
+        table(["a", "b"], &[[1, 2]]),
+        table(["b", "a"], &[[4, 3]]),
+        table(["a", "b"], &[]),
+        table(["a", "b"], &[[5, 6]]),
+    ]);
+    assert_eq!(combined.rows_len(), 3);
+    assert_eq!(combined.get(&"a", 1), Some(&3));
+    assert_eq!(combined.get(&"b", 2), Some(&6));
+}
+
+#[test]
+fn concat_union_tables() {
+    let combined = HashTable::concat_union(
+        [table(["a", "b"], &[[1, 2]]), table(["c", "d"], &[[3, 4]])],
+        |key| if *key == "a" { 100 } else { 0 },
+    );
+    assert_eq!(combined.column_keys_in_order(), [&"a", &"b", &"c", &"d"]);
+    assert_eq!(combined.get(&"a", 1), Some(&100));
+    assert_eq!(combined.get(&"d", 0), Some(&0));
+    assert_eq!(combined.get(&"d", 1), Some(&4));
+}
+
+#[test]
+fn hstack_adds_columns() {
+    let mut left = table(["a", "b"], &[[1, 2], [3, 4]]);
+    left.hstack(table(["d", "c"], &[[10, 20], [30, 40]]));
+    assert_eq!(left.column_keys_in_order(), [&"a", &"b", &"d", &"c"]);
+    assert_eq!(left.get(&"c", 1), Some(&40));
+    assert_eq!(left.get(&"a", 1), Some(&3));
+}
+
+#[test]
+#[should_panic]
+fn hstack_duplicate_column() {
+    table(["a", "b"], &[[1, 2]]).hstack(table(["b", "c"], &[[3, 4]]));
+}
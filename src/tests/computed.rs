@@ -0,0 +1,87 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::{table::computed::ComputedHashTable, HashTable};
+
+fn sample_table() -> ComputedHashTable<&'static str, i32> {
+    HashTable::from_column_keys_and_rows(["a", "b"], [[1, 2], [3, 4]]).into()
+}
+
+#[test]
+fn cached_column_is_computed_once_until_mutation() {
+    let calls = Rc::new(Cell::new(0));
+    let mut table = sample_table();
+    let counter = Rc::clone(&calls);
+    table.add_computed_column("sum", move |row| {
+        counter.set(counter.get() + 1);
+        row.get("a").unwrap() + row.get("b").unwrap()
+    });
+    assert_eq!(*table.get("sum", 1).unwrap(), 7);
+    assert_eq!(*table.get("sum", 1).unwrap(), 7);
+    assert_eq!(calls.get(), 1);
+
+    *table.get_mut("a", 1).unwrap() = 10;
+    assert_eq!(*table.get("sum", 1).unwrap(), 14);
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn computed_columns_are_visible_in_rows() {
+    let mut table = sample_table();
+    table.add_uncached_computed_column("neg", |row| -row.get("a").unwrap());
+    let row = table.get_row(0).unwrap();
+    assert_eq!(row.columns_len(), 3);
+    assert_eq!(row.into_iter().count(), 3);
+    assert_eq!(
+        table
+            .iter()
+            .map(|row| *row.get("neg").unwrap())
+            .collect::<Vec<_>>(),
+        [-1, -3]
+    );
+}
+
+#[test]
+fn materialize_stores_computed_values() {
+    let mut table = sample_table();
+    table.add_computed_column("prod", |row| row.get("a").unwrap() * row.get("b").unwrap());
+    let table = table.materialize();
+    assert_eq!(table.columns_len(), 3);
+    assert_eq!(table.get("prod", 1), Some(&12));
+}
+
+#[test]
+fn missing_get_mut_keeps_cache() {
+    let calls = Rc::new(Cell::new(0));
+    let mut table = sample_table();
+    let counter = Rc::clone(&calls);
+    table.add_computed_column("sum", move |row| {
+        counter.set(counter.get() + 1);
+        row.get("a").unwrap() + row.get("b").unwrap()
+    });
+    assert_eq!(*table.get("sum", 0).unwrap(), 3);
+    assert!(table.get_mut("a", 5).is_none());
+    assert!(table.get_mut("c", 0).is_none());
+    assert_eq!(*table.get("sum", 0).unwrap(), 3);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn stored_column_hides_computed_column() {
+    let mut table = sample_table();
+    table.add_computed_column("sum", |row| row.get("a").unwrap() + row.get("b").unwrap());
+    table.add_computed_column("neg", |row| -row.get("a").unwrap());
+    table.table_mut().insert_column("sum", [0, 0]);
+    assert_eq!(table.columns_len(), 4);
+    assert_eq!(*table.get("sum", 1).unwrap(), 0);
+
+    let row = table.get_row(1).unwrap();
+    assert_eq!(row.columns_len(), 4);
+    let mut keys: Vec<_> = row.into_iter().map(|(key, _)| *key).collect();
+    assert_eq!(keys.len(), row.into_iter().len());
+    keys.sort_unstable();
+    assert_eq!(keys, ["a", "b", "neg", "sum"]);
+
+    let table = table.materialize();
+    assert_eq!(table.columns_len(), 4);
+    assert_eq!(table.get("sum", 1), Some(&0));
+}
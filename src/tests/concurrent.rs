@@ -0,0 +1,76 @@
+use std::thread;
+
+use crate::{
+    table::concurrent::{ConcurrentHashTable, ROWS_PER_CHUNK},
+    HashTable,
+};
+
+#[test]
+fn parallel_appends_keep_rows_intact() {
+    let table = ConcurrentHashTable::with_columns(["producer", "sequence", "check"]);
+    thread::scope(|s| {
+        for producer in 0..8 {
+            let table = &table;
+            s.spawn(move || {
+                for sequence in 0..500 {
+                    let row = table.push_row([
+                        ("producer", producer),
+                        ("sequence", sequence),
+                        ("check", producer * 1000 + sequence),
+                    ]);
+                    assert_eq!(table.get_cloned("sequence", row), Some(sequence));
+                }
+            });
+        }
+    });
+    assert_eq!(table.rows_len(), 4000);
+
+    let table = table.into_table();
+    assert_eq!(table.rows_len(), 4000);
+    for row in &table {
+        let producer = row.get("producer").unwrap();
+        let sequence = row.get("sequence").unwrap();
+        assert_eq!(*row.get("check").unwrap(), producer * 1000 + sequence);
+    }
+}
+
+#[test]
+fn updates_and_snapshots_run_alongside() {
+    let rows = ROWS_PER_CHUNK * 3 + 5;
+    let table: ConcurrentHashTable<&str, u64> =
+        HashTable::from_column_keys_and_rows(["a", "b"], (0..rows).map(|_| [0, 0])).into();
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for row in 0..rows {
+                    table.with_row_mut(row, |row| {
+                        for (_, value) in row {
+                            *value += 1;
+                        }
+                    });
+                }
+            });
+        }
+        s.spawn(|| {
+            for _ in 0..20 {
+                let snapshot = table.snapshot();
+                assert_eq!(snapshot.rows_len(), rows);
+                for row in &snapshot {
+                    assert_eq!(row.get("a"), row.get("b"));
+                }
+            }
+        });
+    });
+    let snapshot = table.snapshot();
+    assert!(snapshot.values_vector.iter().all(|v| *v == 4));
+}
+
+#[test]
+fn out_of_range_access() {
+    let table = ConcurrentHashTable::with_columns(["x"]);
+    assert_eq!(table.push_row_with(|_| 1), 0);
+    assert_eq!(table.update("x", 1, |x| *x = 2), None);
+    assert_eq!(table.update("y", 0, |x| *x = 2), None);
+    assert_eq!(table.with_row(1, |_| ()), None);
+    assert_eq!(table.update("x", 0, |x| *x * 10), Some(10));
+}
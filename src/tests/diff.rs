@@ -0,0 +1,123 @@
+use crate::{table::diff::DiffError, HashTable};
+
+fn old() -> HashTable<String, String> {
+    HashTable::from_column_keys_and_rows(
+        ["id", "name", "price", "legacy"].map(str::to_owned),
+        [
+            ["1", "apple", "10", "x"],
+            ["2", "pear", "12", "y"],
+            ["3", "plum", "7", "z"],
+        ]
+        .map(|row| row.map(str::to_owned)),
+    )
+}
+
+fn new() -> HashTable<String, String> {
+    HashTable::from_column_keys_and_rows(
+        ["id", "stock", "price", "name"].map(str::to_owned),
+        [
+            ["3", "0", "8", "plum"],
+            ["1", "5", "10", "apple"],
+            ["4", "9", "3", "fig"],
+        ]
+        .map(|row| row.map(str::to_owned)),
+    )
+}
+
+#[test]
+fn diff_describes_all_changes() {
+    let diff = old().diff(&new(), ["id"]).unwrap();
+    assert_eq!(diff.added_columns, ["stock"]);
+    assert_eq!(diff.removed_columns, ["legacy"]);
+    assert_eq!(diff.removed_rows, [["2"]]);
+    assert_eq!(diff.added_rows, [["4", "9", "3", "fig"]]);
+    assert_eq!(diff.changed_rows.len(), 2);
+    let plum = &diff.changed_rows[0];
+    assert_eq!(plum.key, ["3"]);
+    assert_eq!(plum.cells[0].column, "price");
+    assert_eq!(plum.cells[0].old.as_deref(), Some("7"));
+    assert_eq!(plum.cells[0].new, "8");
+    assert_eq!(plum.cells[1].old, None);
+
+    assert!(old().diff(&old(), ["id"]).unwrap().is_empty());
+}
+
+#[test]
+fn applied_diff_reproduces_new_table() {
+    let mut table = old();
+    let diff = table.diff(&new(), ["id"]).unwrap();
+    table.apply(&diff).unwrap();
+
+    assert_eq!(
+        table.column_keys_in_order(),
+        ["id", "name", "price", "stock"]
+    );
+    assert!(table.diff(&new(), ["id"]).unwrap().is_empty());
+    assert_eq!(table.rows_len(), 3);
+}
+
+#[test]
+fn invalid_diffs_leave_table_unchanged() {
+    let diff = old().diff(&new(), ["id"]).unwrap();
+    let mut table = old();
+    table.get_mut("price", 2).unwrap().push('0');
+    assert_eq!(table.apply(&diff), Err(DiffError::Conflict(0)));
+    assert_eq!(table.get("name", 1).map(String::as_str), Some("pear"));
+    assert_eq!(table.columns_len(), 4);
+
+    let mut table = old();
+    table.remove_row(1);
+    assert_eq!(table.apply(&diff), Err(DiffError::MissingRemovedRow(0)));
+
+    let mut duplicated = old();
+    duplicated.push_row_with(|_| "1".to_owned());
+    assert_eq!(
+        duplicated.diff(&new(), ["id"]),
+        Err(DiffError::DuplicateKey(3))
+    );
+    assert_eq!(
+        old().diff(&new(), ["missing"]),
+        Err(DiffError::MissingKeyColumn(0))
+    );
+}
+
+#[test]
+fn diffs_with_repeated_columns_are_rejected() {
+    let mut table = HashTable::from_column_keys_and_rows(
+        ["id", "a"].map(str::to_owned),
+        [["1", "x"].map(str::to_owned)],
+    );
+    let diff = table
+        .diff(&HashTable::with_columns(["id".to_owned()]), ["id"])
+        .unwrap();
+
+    let mut removed_twice = diff.clone();
+    removed_twice.removed_columns = vec!["a".to_owned(); 3];
+    assert_eq!(table.apply(&removed_twice), Err(DiffError::ColumnMismatch));
+
+    let mut added_twice = diff.clone();
+    added_twice.added_columns = vec!["b".to_owned(); 2];
+    added_twice
+        .columns
+        .extend(added_twice.added_columns.clone());
+    assert_eq!(table.apply(&added_twice), Err(DiffError::ColumnMismatch));
+
+    let mut columns_twice = diff.clone();
+    columns_twice.removed_columns.clear();
+    columns_twice.columns = vec!["id".to_owned(); 2];
+    assert_eq!(table.apply(&columns_twice), Err(DiffError::ColumnMismatch));
+
+    assert_eq!(table.columns_len(), 2);
+    assert_eq!(table.apply(&diff), Ok(()));
+    assert_eq!(table.columns_len(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn diff_round_trips_through_serde() {
+    let diff = old().diff(&new(), ["id"]).unwrap();
+    let json = serde_json::to_string(&diff).unwrap();
+    let restored: crate::table::diff::TableDiff<String, String> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, diff);
+}
@@ -0,0 +1,112 @@
+use crate::{
+    table::entry::{CellEntry, RowEntry},
+    HashTable,
+};
+
+fn inventory() -> HashTable<&'static str, i32> {
+    HashTable::from_column_keys_and_rows(["id", "stock", "price"], [[1, 5, 10], [2, 0, 20]])
+}
+
+#[test]
+fn occupied_entry() {
+    let mut table = inventory();
+    let entry = table.row_entry(&"id", 2).unwrap();
+    assert_eq!(entry.row(), Some(1));
+    let RowEntry::Occupied(mut entry) = entry else {
+        panic!("Row with id 2 is in the table");
+    };
+    assert_eq!(entry.get().get(&"price"), Some(&20));
+    *entry.get_mut().get(&"stock").unwrap() = 3;
+    assert_eq!(table.get(&"stock", 1), Some(&3));
+}
+
+#[test]
+fn vacant_entry_inserts_key() {
+    let mut table = inventory();
+    let entry = table.row_entry(&"id", 7).unwrap();
+    assert_eq!(entry.row(), None);
+    let mut generated = Vec::new();
+    entry.or_insert_with(|column| {
+        generated.push(*column);
+        -1
+    });
+    generated.sort_unstable();
+    assert_eq!(generated, ["price", "stock"]);
+    assert_eq!(table.rows_len(), 3);
+    assert_eq!(table.get(&"id", 2), Some(&7));
+    assert_eq!(table.get(&"price", 2), Some(&-1));
+}
+
+#[test]
+fn and_modify_or_insert_with() {
+    let mut table: HashTable<&str, i32> = HashTable::with_columns(["value", "count"]);
+    for value in [3, 1, 3, 3, 1, 2] {
+        table
+            .row_entry(&"value", value)
+            .unwrap()
+            .and_modify(|mut row| *row.get(&"count").unwrap() += 1)
+            .or_insert_with(|_| 1);
+    }
+    let counts: Vec<(i32, i32)> = table
+        .iter()
+        .map(|row| (*row.get(&"value").unwrap(), *row.get(&"count").unwrap()))
+        .collect();
+    assert_eq!(counts, [(3, 3), (1, 2), (2, 1)]);
+}
+
+#[test]
+fn remove_and_missing_column() {
+    let mut table = inventory();
+    let RowEntry::Occupied(entry) = table.row_entry(&"id", 1).unwrap() else {
+        panic!("Row with id 1 is in the table");
+    };
+    assert_eq!(entry.remove().get(&"stock"), Some(&5));
+    assert_eq!(table.rows_len(), 1);
+
+    let RowEntry::Vacant(entry) = table.row_entry(&"id", 1).unwrap() else {
+        panic!("Row with id 1 was removed");
+    };
+    assert_eq!(entry.into_key(), 1);
+    assert!(table.row_entry(&"name", 1).is_none());
+}
+
+#[test]
+fn cell_entry() {
+    let mut table = inventory();
+    let CellEntry::Occupied(mut entry) = table.entry(&"price", 1).unwrap() else {
+        panic!("Row 1 is in the table");
+    };
+    assert_eq!(entry.get(), &20);
+    assert_eq!(entry.insert(25), 20);
+    *entry.into_mut() += 1;
+    assert_eq!(table.get(&"price", 1), Some(&26));
+
+    table
+        .entry(&"stock", 0)
+        .unwrap()
+        .and_modify(|stock| *stock -= 1);
+    assert_eq!(table.get(&"stock", 0), Some(&4));
+
+    assert!(table.entry(&"missing", 0).is_none());
+    assert!(table.entry(&"stock", 3).is_none());
+    assert_eq!(table.rows_len(), 2);
+}
+
+#[test]
+fn vacant_cell_entry_adds_row() {
+    let mut table = inventory();
+    let value = table
+        .entry(&"stock", 2)
+        .unwrap()
+        .and_modify(|_| panic!("Row 2 is not in the table"))
+        .or_insert_with(|column| if *column == "id" { 3 } else { 0 });
+    *value += 8;
+    assert_eq!(table.rows_len(), 3);
+    assert_eq!(table.get(&"id", 2), Some(&3));
+    assert_eq!(table.get(&"stock", 2), Some(&8));
+    assert_eq!(table.get(&"price", 2), Some(&0));
+
+    *table.entry(&"price", 3).unwrap().or_default() = 4;
+    assert_eq!(table.get(&"id", 3), Some(&0));
+    assert_eq!(table.get(&"price", 3), Some(&4));
+}
@@ -0,0 +1,139 @@
+use crate::{
+    expr::{col, lit, Expr, ExprError},
+    value::Value,
+    HashTable,
+};
+
+fn people() -> HashTable<&'static str, Value> {
+    HashTable::from_column_keys_and_rows(
+        ["name", "age"],
+        [
+            [Value::from("Kari"), Value::Int(34)],
+            [Value::from("Ola"), Value::Int(19)],
+            [Value::from("Nora"), Value::Int(51)],
+        ],
+    )
+}
+
+#[test]
+fn parse_respects_precedence() {
+    let parsed = Expr::parse("a + 2 * b > 10 || !flag").unwrap();
+    let built = (col("a") + lit(2) * col("b")).gt(10).or(!col("flag"));
+    assert_eq!(parsed, built);
+}
+
+#[test]
+fn display_round_trips() {
+    let expr = Expr::parse(r#"-(x % 3) <= 1.5 && col("some name") != 'it\'s'"#).unwrap();
+    assert_eq!(Expr::parse(&expr.to_string()).unwrap(), expr);
+
+    for expr in [
+        lit(i64::MIN),
+        lit(-5) - lit(-2.5),
+        -lit(3),
+        lit(1e20) * lit(1e-7),
+        lit("line\r\nbreak\t\u{1b}[0m \\ \" '"),
+        col("\u{7f}odd\r\"name\""),
+    ] {
+        assert_eq!(Expr::parse(&expr.to_string()), Ok(expr));
+    }
+    assert_eq!(Expr::parse("-9223372036854775808"), Ok(lit(i64::MIN)));
+    assert!(Expr::parse("9223372036854775808").is_err());
+}
+
+#[test]
+fn parse_errors_report_position() {
+    assert!(matches!(
+        Expr::parse("age > "),
+        Err(ExprError::Parse { position: 6, .. })
+    ));
+    assert!(matches!(
+        Expr::parse("age = 3"),
+        Err(ExprError::Parse { position: 4, .. })
+    ));
+}
+
+#[test]
+fn eval_arithmetic_and_nulls() {
+    let eval = |s: &str| Expr::parse(s).unwrap().eval_with(&|_| None);
+    assert_eq!(eval("7 / 2"), Ok(Value::Int(3)));
+    assert_eq!(eval("7 / 2.0"), Ok(Value::Float(3.5)));
+    assert_eq!(eval("'a' + 'b'"), Ok(Value::from("ab")));
+    assert_eq!(eval("null + 1"), Ok(Value::Null));
+    assert_eq!(eval("1 / 0"), Err(ExprError::DivisionByZero));
+    assert!(matches!(eval("1 + 'a'"), Err(ExprError::Type { .. })));
+}
+
+#[test]
+fn filter_sort_and_select() {
+    let mut table = people();
+    let adults = table
+        .filter_expr(&Expr::parse("age >= 30").unwrap())
+        .unwrap();
+    assert_eq!(adults.rows_len(), 2);
+
+    table.sort_by_expr(&col("age"), true).unwrap();
+    assert_eq!(table.get("name", 0), Some(&Value::from("Nora")));
+
+    let projected = table.select_expr([("next_year", col("age") + 1)]).unwrap();
+    assert_eq!(projected.columns_len(), 1);
+    assert_eq!(projected.get("next_year", 2), Some(&Value::Int(20)));
+    assert_eq!(
+        table
+            .select_expr([("x", col("age")), ("y", lit(1)), ("x", lit(2))])
+            .unwrap_err(),
+        ExprError::DuplicateColumn("x".to_owned())
+    );
+
+    assert_eq!(
+        table.filter_expr(&col("missing").gt(1)).unwrap_err(),
+        ExprError::UnknownColumn("missing".to_owned())
+    );
+}
+
+#[test]
+fn retain_and_select_columns() {
+    let mut table = people();
+    table.retain(|row| row.get("age") != Some(&Value::Int(19)));
+    assert_eq!(table.rows_len(), 2);
+    let names = table.select(["name"]);
+    assert_eq!(names.columns_len(), 1);
+    assert_eq!(names.get("name", 1), Some(&Value::from("Nora")));
+}
+
+#[test]
+fn numbers_compare_exactly() {
+    use std::cmp::Ordering::*;
+
+    let big = 1_i64 << 53;
+    let (int, float) = (Value::Int(big + 1), Value::Float(big as f64));
+    assert_eq!(int.compare(&float), Some(Greater));
+    assert_eq!(int.total_cmp(&float), Greater);
+    assert_eq!(float.total_cmp(&Value::Int(big)), Equal);
+
+    assert_eq!(Value::Int(2).compare(&Value::Float(2.5)), Some(Less));
+    assert_eq!(Value::Int(-2).compare(&Value::Float(-2.5)), Some(Greater));
+    assert_eq!(Value::Int(0).compare(&Value::Float(-0.0)), Some(Equal));
+    assert_eq!(Value::Float(-0.0).total_cmp(&Value::Float(0.0)), Equal);
+    assert_eq!(
+        Value::Int(i64::MAX).compare(&Value::Float(2f64.powi(63))),
+        Some(Less)
+    );
+    assert_eq!(
+        Value::Int(i64::MIN).compare(&Value::Float(-(2f64.powi(63)))),
+        Some(Equal)
+    );
+    assert_eq!(
+        Value::Int(i64::MIN).compare(&Value::Float(f64::NEG_INFINITY)),
+        Some(Greater)
+    );
+
+    let nan = Value::Float(f64::NAN);
+    assert_eq!(Value::Int(1).compare(&nan), None);
+    assert_eq!(Value::Int(i64::MAX).total_cmp(&nan), Less);
+    assert_eq!(nan.total_cmp(&Value::Float(f64::INFINITY)), Greater);
+    assert_eq!(
+        Value::Float(-f64::NAN).total_cmp(&Value::Int(i64::MIN)),
+        Less
+    );
+}
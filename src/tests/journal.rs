@@ -0,0 +1,160 @@
+use crate::{table::journal::JournaledHashTable, HashTable};
+
+fn sample() -> JournaledHashTable<&'static str, i32> {
+    HashTable::from_column_keys_and_rows(["a", "b", "c"], [[1, 2, 3], [4, 5, 6], [7, 8, 9]]).into()
+}
+
+fn values(table: &JournaledHashTable<&'static str, i32>) -> Vec<i32> {
+    table.table().values_vector.clone()
+}
+
+#[test]
+fn undo_and_redo_every_operation() {
+    let mut table = sample();
+    let original = values(&table);
+
+    *table.get_mut("b", 1).unwrap() = 50;
+    assert_eq!(table.set("c", 2, 90), Some(9));
+    table.push_row([("a", 10), ("c", 12), ("b", 11)]);
+    assert_eq!(table.remove_row(0).unwrap().get("a"), Some(&1));
+    table.insert_column("d", [0, 0, 0]);
+    assert_eq!(table.remove_column("a"), Some(&[4, 7, 10][..]));
+    assert_eq!(table.table().column_keys_in_order(), [&"b", &"c", &"d"]);
+    let changed = values(&table);
+
+    while table.undo() {}
+    assert_eq!(values(&table), original);
+    assert_eq!(table.table().column_keys_in_order(), [&"a", &"b", &"c"]);
+    assert!(!table.can_undo());
+
+    while table.redo() {}
+    assert_eq!(values(&table), changed);
+    assert_eq!(table.table().column_keys_in_order(), [&"b", &"c", &"d"]);
+}
+
+#[test]
+fn removed_column_is_restored_in_place() {
+    let mut table = sample();
+    table.remove_column("b");
+    assert_eq!(table.table().values_vector, [1, 3, 4, 6, 7, 9]);
+    table.undo();
+    assert_eq!(table.table().column_keys_in_order(), [&"a", &"b", &"c"]);
+    assert_eq!(table.table().values_vector, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn new_change_discards_redo() {
+    let mut table = sample();
+    table.set("a", 0, 100);
+    table.undo();
+    assert!(table.can_redo());
+    table.set("a", 0, 200);
+    assert!(!table.can_redo());
+    assert!(!table.redo());
+}
+
+#[test]
+fn transactions_are_atomic() {
+    let mut table = sample();
+    let result: Result<(), ()> = table.transaction(|t| {
+        t.set("a", 0, 10);
+        t.remove_row(2);
+        t.transaction(|t| {
+            t.set("b", 0, 20);
+            Ok::<_, ()>(())
+        })
+    });
+    assert!(result.is_ok());
+    assert_eq!(table.table().rows_len(), 2);
+    table.undo();
+    assert_eq!(values(&table), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    table.redo();
+
+    let result = table.transaction(|t| {
+        t.set("c", 0, 30);
+        t.transaction(|t| {
+            t.remove_column("a");
+            Err::<(), _>("inner")
+        })?;
+        Ok(())
+    });
+    assert_eq!(result, Err("inner"));
+    assert_eq!(values(&table), [10, 20, 3, 4, 5, 6]);
+    assert!(table.undo());
+    assert!(!table.can_undo());
+}
+
+#[test]
+fn panicking_transaction_is_reverted() {
+    let mut table = sample();
+    table.set("a", 0, 10);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        table.transaction(|t| {
+            t.remove_row(2);
+            t.transaction(|t| -> Result<(), ()> {
+                t.set("b", 0, 20);
+                panic!("inner");
+            })
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(values(&table), [10, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    table.set("c", 0, 30);
+    assert!(table.undo());
+    assert!(table.undo());
+    assert_eq!(values(&table), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert!(!table.can_undo());
+}
+
+#[test]
+fn rollback_to_checkpoint() {
+    let mut table = sample();
+    table.set("a", 0, 10);
+    let checkpoint = table.checkpoint();
+    table.set("a", 1, 40);
+    table.push_row_with(|_| 0);
+    assert!(table.rollback_to(checkpoint));
+    assert_eq!(values(&table), [10, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert!(table.can_redo());
+
+    table.undo();
+    table.set("b", 0, 0);
+    assert!(!table.rollback_to(checkpoint));
+    assert_eq!(table.get("b", 0), Some(&0));
+}
+
+#[test]
+fn malformed_row_is_not_pushed() {
+    for row in [
+        vec![("a", 0), ("b", 0)],
+        vec![("a", 0), ("b", 0), ("c", 0), ("d", 0)],
+        vec![("a", 0), ("b", 0), ("b", 0)],
+    ] {
+        let mut table = sample();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            table.push_row(row.clone());
+        }));
+        assert!(result.is_err(), "{row:?}");
+        assert_eq!(values(&table), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert!(!table.can_undo());
+    }
+}
+
+#[test]
+fn removed_values_are_not_cloned() {
+    // Doesn't implement `Clone`
+    #[derive(Debug, PartialEq)]
+    struct Cell(i32);
+
+    let mut table = JournaledHashTable::new(HashTable::from_column_keys_and_rows(
+        ["a", "b"],
+        [[Cell(1), Cell(2)], [Cell(3), Cell(4)]],
+    ));
+    assert_eq!(table.remove_row(0).unwrap().get("b"), Some(&Cell(2)));
+    assert_eq!(table.remove_column("a"), Some(&[Cell(3)][..]));
+    assert!(table.undo());
+    assert!(table.undo());
+    assert_eq!(table.get("a", 0), Some(&Cell(1)));
+    assert_eq!(table.get("b", 1), Some(&Cell(4)));
+}
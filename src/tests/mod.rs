@@ -1,2 +1,31 @@
+#[cfg(feature = "arrow")]
+mod arrow;
+mod binary;
+mod columns;
+mod combine;
+mod computed;
+mod concurrent;
+mod diff;
+mod entry;
+mod expr;
+mod journal;
+mod observable;
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "query")]
+mod query;
+#[cfg(feature = "derive")]
+mod record;
+mod rows;
+#[cfg(feature = "serde")]
+mod serde;
+mod set_ops;
+mod shared;
+mod stats;
+mod typed;
+mod window;
 
-
+/// Table with two columns of `i32` values
+fn table(columns: [&'static str; 2], rows: &[[i32; 2]]) -> crate::HashTable<&'static str, i32> {
+    crate::HashTable::from_column_keys_and_rows(columns, rows.iter().copied())
+}
@@ -0,0 +1,64 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    table::observable::{ObservableHashTable, TableEvent},
+    HashTable,
+};
+
+/// Table that logs its events as strings
+fn logged() -> (ObservableHashTable<String, i32>, Rc<RefCell<Vec<String>>>) {
+    let mut table: ObservableHashTable<_, _> =
+        HashTable::from_column_keys_and_rows(["a".to_owned()], [[1], [2]]).into();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let events = Rc::clone(&log);
+    table.subscribe(move |event| {
+        events.borrow_mut().push(match event {
+            TableEvent::RowInserted { row } => format!("+row {row}"),
+            TableEvent::RowRemoved { row } => format!("-row {row}"),
+            TableEvent::CellUpdated { row, column } => format!("cell {row} {column}"),
+            TableEvent::ColumnAdded { column } => format!("+column {column}"),
+            TableEvent::ColumnRemoved { column } => format!("-column {column}"),
+        })
+    });
+    (table, log)
+}
+
+#[test]
+fn every_change_is_reported() {
+    let (mut table, log) = logged();
+    table.push_row([("a".to_owned(), 3)]);
+    table.insert_column("b".to_owned(), [4, 5, 6]);
+    assert_eq!(table.set("b", 2, 60), Some(6));
+    assert_eq!(table.remove_row(0).unwrap().get("a"), Some(&1));
+    assert_eq!(table.remove_column("a").unwrap().into_values(), [2, 3]);
+    assert_eq!(
+        *log.borrow(),
+        ["+row 2", "+column b", "cell 2 b", "-row 0", "-column a"]
+    );
+    assert_eq!(table.table().values_vector, [5, 60]);
+}
+
+#[test]
+fn failed_changes_are_not_reported() {
+    let (mut table, log) = logged();
+    assert_eq!(table.set("a", 5, 0), None);
+    assert_eq!(table.update("b", 0, |_| ()), None);
+    assert!(table.remove_row(2).is_none());
+    assert!(table.remove_column("b").is_none());
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn unsubscribed_observers_are_not_called() {
+    let (mut table, log) = logged();
+    let calls = Rc::new(RefCell::new(0));
+    let counter = Rc::clone(&calls);
+    let id = table.subscribe(move |_| *counter.borrow_mut() += 1);
+    table.update("a", 0, |a| *a += 1);
+    assert!(table.unsubscribe(id));
+    assert!(!table.unsubscribe(id));
+    table.update("a", 0, |a| *a += 1);
+    assert_eq!(*calls.borrow(), 1);
+    assert_eq!(log.borrow().len(), 2);
+    assert_eq!(table.get("a", 0), Some(&3));
+}
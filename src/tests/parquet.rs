@@ -0,0 +1,104 @@
+use std::fs::File;
+
+use ::parquet::file::reader::{FileReader, SerializedFileReader};
+
+use crate::{
+    parquet::{Compression, ParquetWriteOptions, ZstdLevel},
+    value::Value,
+    HashTable,
+};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("hash_table_{}_{name}.parquet", std::process::id()))
+}
+
+#[test]
+fn round_trip_keeps_column_order() {
+    let table = HashTable::from_column_keys_and_rows(
+        ["name", "score", "note"].map(str::to_owned),
+        [
+            [Value::from("a"), Value::Int(3), Value::Null],
+            [Value::from("b"), Value::Float(1.5), Value::from("late")],
+        ],
+    );
+    let path = temp_path("round_trip");
+    table
+        .write_parquet(&path, &ParquetWriteOptions::default())
+        .unwrap();
+    let restored = HashTable::<String, Value>::read_parquet(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        restored.column_keys_in_order(),
+        table.column_keys_in_order()
+    );
+    assert_eq!(restored.rows_len(), 2);
+    assert_eq!(restored.get("score", 0), Some(&Value::Float(3.0)));
+    assert_eq!(restored.get("note", 0), Some(&Value::Null));
+    assert_eq!(restored.get("note", 1), Some(&Value::from("late")));
+}
+
+#[test]
+fn rows_are_split_into_row_groups() {
+    let table = HashTable::from_column_iter([
+        ("id".to_owned(), (0..10_i64).collect::<Vec<_>>()),
+        ("square".to_owned(), (0..10_i64).map(|i| i * i).collect()),
+    ]);
+    let path = temp_path("row_groups");
+    let options = ParquetWriteOptions::default()
+        .with_row_group_size(4)
+        .with_compression(Compression::ZSTD(ZstdLevel::default()));
+    table.write_parquet(&path, &options).unwrap();
+
+    let metadata = SerializedFileReader::new(File::open(&path).unwrap())
+        .unwrap()
+        .metadata()
+        .clone();
+    assert_eq!(metadata.num_row_groups(), 3);
+    assert_eq!(
+        metadata.row_group(0).column(0).compression(),
+        Compression::ZSTD(ZstdLevel::default())
+    );
+
+    let restored = HashTable::<String, i64>::read_parquet(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(restored.rows_len(), 10);
+    assert_eq!(restored.get("square", 9), Some(&81));
+}
+
+#[test]
+fn reading_mismatched_types_fails() {
+    let table = HashTable::from_column_keys_and_rows(["s".to_owned()], [["x".to_owned()]]);
+    let path = temp_path("mismatch");
+    table
+        .write_parquet(&path, &ParquetWriteOptions::default())
+        .unwrap();
+    let result = HashTable::<String, i64>::read_parquet(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(result.is_err());
+}
+
+#[test]
+fn reading_duplicate_columns_fails() {
+    use std::sync::Arc;
+
+    use ::parquet::arrow::ArrowWriter;
+    use arrow_array::{Int64Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+
+    let field = Field::new("a", DataType::Int64, false);
+    let schema = Arc::new(Schema::new(vec![field.clone(), field]));
+    let column = Arc::new(Int64Array::from(vec![1, 2]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![column.clone(), column]).unwrap();
+    let path = temp_path("duplicate");
+    let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let result = HashTable::<String, i64>::read_parquet(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Parquet error: duplicate column `a`"
+    );
+}
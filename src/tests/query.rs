@@ -0,0 +1,177 @@
+use crate::{
+    query::{Catalog, QueryError},
+    value::Value,
+    HashTable,
+};
+
+fn employees() -> HashTable<String, Value> {
+    HashTable::from_column_keys_and_rows(
+        ["name", "dept", "salary"].map(str::to_owned),
+        [
+            [Value::from("Ada"), Value::from("eng"), Value::Int(120)],
+            [Value::from("Bob"), Value::from("ops"), Value::Int(80)],
+            [Value::from("Cy"), Value::from("eng"), Value::Int(100)],
+            [Value::from("Di"), Value::from("ops"), Value::Null],
+        ],
+    )
+}
+
+#[test]
+fn select_where_order_limit() {
+    let table = employees();
+    let mut catalog = Catalog::new();
+    catalog.add("employees", &table);
+    let result = catalog
+        .query(
+            "select name, salary * 2 as double from employees \
+             where salary >= 90 and not dept = 'ops' order by salary asc limit 1",
+        )
+        .unwrap();
+    assert_eq!(result.rows_len(), 1);
+    assert_eq!(result.columns_len(), 2);
+    assert_eq!(result.get("name", 0), Some(&Value::from("Cy")));
+    assert_eq!(result.get("double", 0), Some(&Value::Int(200)));
+}
+
+#[test]
+fn group_by_aggregates() {
+    let table = employees();
+    let mut catalog = Catalog::new();
+    catalog.add("employees", &table);
+    let result = catalog
+        .query(
+            "SELECT dept, count(*), count(salary) AS paid, avg(salary) AS avg \
+             FROM employees GROUP BY dept ORDER BY dept",
+        )
+        .unwrap();
+    assert_eq!(result.rows_len(), 2);
+    assert_eq!(result.get("dept", 1), Some(&Value::from("ops")));
+    assert_eq!(result.get("count(*)", 1), Some(&Value::Int(2)));
+    assert_eq!(result.get("paid", 1), Some(&Value::Int(1)));
+    assert_eq!(result.get("avg", 0), Some(&Value::Float(110.0)));
+}
+
+#[test]
+fn wildcard_keeps_column_order_and_errors() {
+    let table = employees();
+    let mut catalog = Catalog::new();
+    catalog.add("employees", &table);
+    let result = catalog
+        .query("SELECT * FROM employees LIMIT 2 OFFSET 1")
+        .unwrap();
+    assert_eq!(result.columns_len(), 3);
+    assert_eq!(result.get("name", 0), Some(&Value::from("Bob")));
+
+    assert_eq!(
+        catalog.query("SELECT * FROM missing").unwrap_err(),
+        QueryError::UnknownTable("missing".to_owned())
+    );
+    assert_eq!(
+        catalog
+            .query("SELECT name, name FROM employees")
+            .unwrap_err(),
+        QueryError::DuplicateColumn("name".to_owned())
+    );
+    assert!(matches!(
+        catalog.query("SELECT FROM employees"),
+        Err(QueryError::Expr(_))
+    ));
+}
+
+fn departments() -> HashTable<String, Value> {
+    HashTable::from_column_keys_and_rows(
+        ["dept", "name", "floor"].map(str::to_owned),
+        [
+            [
+                Value::from("eng"),
+                Value::from("Engineering"),
+                Value::Int(3),
+            ],
+            [Value::from("hr"), Value::from("People"), Value::Int(1)],
+        ],
+    )
+}
+
+#[test]
+fn inner_and_left_joins() {
+    let (employees, departments) = (employees(), departments());
+    let mut catalog = Catalog::new();
+    catalog.add("employees", &employees);
+    catalog.add("departments", &departments);
+
+    let result = catalog
+        .query(
+            "SELECT e.name AS employee, d.name AS department, floor FROM employees AS e \
+             JOIN departments d ON e.dept = d.dept ORDER BY salary DESC",
+        )
+        .unwrap();
+    assert_eq!(result.rows_len(), 2);
+    assert_eq!(result.get("employee", 1), Some(&Value::from("Cy")));
+    assert_eq!(
+        result.get("department", 1),
+        Some(&Value::from("Engineering"))
+    );
+    assert_eq!(result.get("floor", 1), Some(&Value::Int(3)));
+
+    let result = catalog
+        .query(
+            "SELECT * FROM employees LEFT OUTER JOIN departments \
+             ON employees.dept = departments.dept WHERE salary < 110",
+        )
+        .unwrap();
+    assert_eq!(
+        result.column_keys_in_order(),
+        [
+            "employees.name",
+            "employees.dept",
+            "salary",
+            "departments.dept",
+            "departments.name",
+            "floor"
+        ]
+    );
+    assert_eq!(result.rows_len(), 2);
+    assert_eq!(result.get("employees.name", 0), Some(&Value::from("Bob")));
+    assert_eq!(result.get("floor", 0), Some(&Value::Null));
+    assert_eq!(result.get("floor", 1), Some(&Value::Int(3)));
+
+    let result = catalog
+        .query(
+            "SELECT d.dept, count(e.name) AS staff FROM departments d \
+             LEFT JOIN employees e ON e.dept = d.dept GROUP BY d.dept ORDER BY d.dept",
+        )
+        .unwrap();
+    assert_eq!(result.get("staff", 0), Some(&Value::Int(2)));
+    assert_eq!(result.get("staff", 1), Some(&Value::Int(0)));
+}
+
+#[test]
+fn join_errors() {
+    let (employees, departments) = (employees(), departments());
+    let mut catalog = Catalog::new();
+    catalog.add("employees", &employees);
+    catalog.add("departments", &departments);
+
+    assert_eq!(
+        catalog
+            .query("SELECT name FROM employees JOIN departments ON true")
+            .unwrap_err(),
+        QueryError::AmbiguousColumn("name".to_owned())
+    );
+    assert_eq!(
+        catalog
+            .query("SELECT * FROM employees e JOIN departments e ON true")
+            .unwrap_err(),
+        QueryError::DuplicateTable("e".to_owned())
+    );
+    assert_eq!(
+        catalog
+            .query("SELECT * FROM employees JOIN missing ON true")
+            .unwrap_err(),
+        QueryError::UnknownTable("missing".to_owned())
+    );
+    assert!(matches!(
+        catalog.query("SELECT * FROM employees JOIN departments"),
+        Err(QueryError::Expr(_))
+    ));
+}
@@ -0,0 +1,149 @@
+use crate::{
+    record::{RecordError, TableRow},
+    value::Value,
+    HashTable,
+};
+
+#[derive(Debug, Clone, PartialEq, TableRow)]
+struct Point {
+    x: f64,
+    #[table(rename = "height")]
+    y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, TableRow)]
+struct Person {
+    name: String,
+    age: i64,
+    nickname: Option<String>,
+    score: f64,
+    #[table(skip)]
+    cache: Vec<u8>,
+}
+
+fn people() -> Vec<Person> {
+    vec![
+        Person {
+            name: "Ann".to_owned(),
+            age: 31,
+            nickname: None,
+            score: 7.5,
+            cache: vec![1],
+        },
+        Person {
+            name: "Bob".to_owned(),
+            age: 45,
+            nickname: Some("B".to_owned()),
+            score: 3.0,
+            cache: Vec::new(),
+        },
+    ]
+}
+
+#[test]
+fn derived_columns() {
+    assert_eq!(<Point as TableRow<f64>>::COLUMNS, ["x", "height"]);
+    assert_eq!(
+        <Person as TableRow<Value>>::COLUMNS,
+        ["name", "age", "nickname", "score"]
+    );
+}
+
+#[test]
+fn homogeneous_records_round_trip() {
+    let points = [Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }];
+    let mut table = HashTable::<&str, f64>::from_records(points.clone());
+    assert_eq!(table.column_keys_in_order(), [&"x", &"height"]);
+    assert_eq!(table.get(&"height", 1), Some(&4.0));
+
+    table.push_record(&Point { x: 5.0, y: 6.0 });
+    let records: Vec<Point> = table.records().collect::<Result<_, _>>().unwrap();
+    assert_eq!(records[..2], points);
+    assert_eq!(records[2], Point { x: 5.0, y: 6.0 });
+}
+
+#[test]
+fn value_records_round_trip() {
+    let table = HashTable::<String, Value>::from_records(people());
+    assert_eq!(table.get("nickname", 0), Some(&Value::Null));
+    assert_eq!(table.get("age", 1), Some(&Value::Int(45)));
+
+    let records: Vec<Person> = table.records().collect::<Result<_, _>>().unwrap();
+    let mut expected = people();
+    expected.iter_mut().for_each(|person| person.cache.clear());
+    assert_eq!(records, expected);
+}
+
+#[derive(Debug, PartialEq, TableRow)]
+struct Counter {
+    count: u64,
+    size: usize,
+}
+
+#[test]
+fn wide_integers_round_trip() {
+    let counters = [
+        Counter {
+            count: 1 << 63,
+            size: 7,
+        },
+        Counter {
+            count: u64::MAX - (1 << 11) + 1,
+            size: usize::MAX / 2 + 1,
+        },
+    ];
+    let mut table = HashTable::<&str, Value>::with_columns(["count", "size"]);
+    for counter in &counters {
+        table.push_record(counter);
+    }
+    assert_eq!(
+        table.get(&"count", 0),
+        Some(&Value::Float(9223372036854775808.0))
+    );
+    let records: Vec<Counter> = table.records().collect::<Result<_, _>>().unwrap();
+    assert_eq!(records, counters);
+
+    // Rounded up to 2^64 when stored
+    table.push_record(&Counter {
+        count: u64::MAX,
+        size: 0,
+    });
+    assert_eq!(
+        table.records::<Counter>().nth(2),
+        Some(Err(RecordError::InvalidValue("count")))
+    );
+    *table.get_mut(&"size", 0).unwrap() = Value::Float(7.5);
+    assert_eq!(
+        table.records::<Counter>().next(),
+        Some(Err(RecordError::InvalidValue("size")))
+    );
+}
+
+#[test]
+fn push_record_matches_columns_by_key() {
+    let mut table = HashTable::from_column_keys_and_rows(["height", "x"], [[2.0, 1.0]]);
+    table.push_record(&Point { x: 3.0, y: 4.0 });
+    assert_eq!(table.get(&"height", 1), Some(&4.0));
+    assert_eq!(table.get(&"x", 1), Some(&3.0));
+}
+
+#[test]
+#[should_panic]
+fn push_record_with_other_columns() {
+    HashTable::<&str, f64>::with_columns(["x", "y"]).push_record(&Point { x: 1.0, y: 2.0 });
+}
+
+#[test]
+fn record_errors() {
+    let mut table = HashTable::<&str, Value>::from_records(people());
+    *table.get_mut(&"age", 1).unwrap() = Value::from("old");
+    let records: Vec<_> = table.records::<Person>().collect();
+    assert!(records[0].is_ok());
+    assert_eq!(records[1], Err(RecordError::InvalidValue("age")));
+
+    table.remove_column(&"score");
+    assert_eq!(
+        table.records::<Person>().next(),
+        Some(Err(RecordError::MissingColumn("score")))
+    );
+}
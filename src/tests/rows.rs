@@ -0,0 +1,96 @@
+use crate::HashTable;
+
+fn sample_table() -> HashTable<&'static str, i32> {
+    HashTable::from_column_keys_and_rows(["a", "b", "c"], [[1, 2, 3], [4, 5, 6], [7, 8, 9]])
+}
+
+#[test]
+fn get_many_mut_swaps_cells() {
+    let mut table = sample_table();
+    let [a, c] = table.get_many_mut([(&"a", 2), (&"c", 0)]).unwrap();
+    std::mem::swap(a, c);
+    assert_eq!(table.get(&"a", 2), Some(&3));
+    assert_eq!(table.get(&"c", 0), Some(&7));
+}
+
+#[test]
+fn get_many_mut_rejects_invalid_cells() {
+    let mut table = sample_table();
+    assert!(table.get_many_mut([(&"a", 0), (&"a", 0)]).is_none());
+    assert!(table.get_many_mut([(&"a", 0), (&"d", 1)]).is_none());
+    assert!(table.get_many_mut([(&"a", 3)]).is_none());
+    assert!(table.get_many_mut::<&str, 0>([]).is_some());
+}
+
+#[test]
+fn get_rows_mut_disjoint_rows() {
+    let mut table = sample_table();
+    let [mut first, mut last] = table.get_rows_mut([0, 2]).unwrap();
+    std::mem::swap(first.get(&"b").unwrap(), last.get(&"b").unwrap());
+    assert_eq!(table.get(&"b", 0), Some(&8));
+    assert_eq!(table.get(&"b", 2), Some(&2));
+    assert_eq!(table.get(&"b", 1), Some(&5));
+
+    assert!(table.get_rows_mut([1, 1]).is_none());
+    assert!(table.get_rows_mut([0, 3]).is_none());
+}
+
+#[test]
+fn mutable_row_get_repeatedly() {
+    let mut table = sample_table();
+    let mut row = table.get_row_mut(1).unwrap();
+    let a = *row.get(&"a").unwrap();
+    *row.get(&"b").unwrap() += a;
+    *row.get(&"c").unwrap() += a;
+    assert_eq!(table.get_row(1).unwrap().to_vec(), [4, 9, 10]);
+}
+
+#[test]
+fn mutable_row_get_many_mut() {
+    let mut table = sample_table();
+    let mut row = table.get_row_mut(1).unwrap();
+    let [a, b, c] = row.get_many_mut([&"a", &"b", &"c"]).unwrap();
+    *a += *b + *c;
+    assert!(row.get_many_mut([&"a", &"a"]).is_none());
+    assert!(row.get_many_mut([&"x"]).is_none());
+    assert_eq!(table.get(&"a", 1), Some(&15));
+}
+
+#[test]
+fn borrowed_row_accessors() {
+    let table = sample_table();
+    let row = table.get_row(1).unwrap();
+    assert_eq!(row.values().copied().collect::<Vec<_>>(), [4, 5, 6]);
+    assert_eq!(row.to_vec(), [4, 5, 6]);
+    assert_eq!(row.get_index(2), Some(&6));
+    assert_eq!(row.get_index(3), None);
+    assert_eq!(row.get_many([&"c", &"a"]), Some([&6, &4]));
+    assert_eq!(row.get_many([&"a", &"d"]), None);
+    assert!(row.contains_column(&"b"));
+    assert!(!row.contains_column(&"d"));
+
+    let map = row.to_hashmap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[&"b"], 5);
+}
+
+#[test]
+fn rows_compare_by_key() {
+    let table = sample_table();
+    let reordered = HashTable::from_column_keys_and_rows(["c", "a", "b"], [[3, 1, 2], [0, 1, 2]]);
+    let fewer = HashTable::from_column_keys_and_rows(["a", "b"], [[1, 2]]);
+    let row = table.get_row(0).unwrap();
+    assert_eq!(row, reordered.get_row(0).unwrap());
+    assert_ne!(row, reordered.get_row(1).unwrap());
+    assert_ne!(row, fewer.get_row(0).unwrap());
+    assert_ne!(row, table.get_row(1).unwrap());
+}
+
+#[test]
+fn row_views_in_computed_columns() {
+    let mut table = sample_table();
+    table.insert_column_with("sum", |row| row.values().sum());
+    table.insert_column_with("first", |row| *row.get_index(0).unwrap());
+    assert_eq!(table.get(&"sum", 2), Some(&24));
+    assert_eq!(table.get(&"first", 1), Some(&4));
+}
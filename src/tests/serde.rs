@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    column::owned::HashTableColumnOwned, table::serde_impls::hashtable_header_rows, typedefs::Hash,
+    HashTable,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Export {
+    #[serde(with = "hashtable_header_rows")]
+    table: HashTable<String, i32>,
+}
+
+#[test]
+fn header_rows_round_trip() {
+    let table = HashTable::from_column_keys_and_rows(
+        ["b", "a"].map(str::to_owned),
+        [[1, 2], [3, 4], [5, 6]],
+    );
+    let json = serde_json::to_value(Export { table }).unwrap();
+    assert_eq!(
+        json,
+        json!({ "table": { "columns": ["b", "a"], "rows": [[1, 2], [3, 4], [5, 6]] } })
+    );
+
+    let Export { table } = serde_json::from_value(json).unwrap();
+    assert_eq!(table.column_keys_in_order(), ["b", "a"]);
+    assert_eq!(table.rows_len(), 3);
+    assert_eq!(table.get("a", 2), Some(&6));
+}
+
+#[test]
+fn header_rows_rejects_wrong_row_length() {
+    let json = json!({ "table": { "columns": ["a", "b"], "rows": [[1, 2], [3]] } });
+    let error = serde_json::from_value::<Export>(json).unwrap_err();
+    assert!(error.to_string().contains("row 1 has 1 values, expected 2"));
+
+    let json = json!({ "table": { "columns": ["a", "b"], "rows": [[1, 2, 3]] } });
+    let error = serde_json::from_value::<Export>(json).unwrap_err();
+    assert!(error.to_string().contains("row 0 has more than 2 values"));
+
+    // Parsed from a string, as `json!` may reorder the fields
+    let json = r#"{ "table": { "rows": [[1, 2]], "columns": ["a"] } }"#;
+    let error = serde_json::from_str::<Export>(json).unwrap_err();
+    assert!(error.to_string().contains("row 0 has 2 values, expected 1"));
+
+    let json = json!({ "table": { "columns": ["a", "a"], "rows": [] } });
+    let error = serde_json::from_value::<Export>(json).unwrap_err();
+    assert!(error.to_string().contains("duplicate column `a`"));
+
+    let json = json!({ "table": { "columns": ["a"] } });
+    assert!(serde_json::from_value::<Export>(json).is_err());
+}
+
+#[test]
+fn header_rows_accepts_any_field_order() {
+    let json = r#"{ "table": { "rows": [[1, 2], [3, 4]], "version": 2, "columns": ["a", "b"] } }"#;
+    let Export { table } = serde_json::from_str(json).unwrap();
+    assert_eq!(table.column_keys_in_order(), ["a", "b"]);
+    assert_eq!(table.get("b", 1), Some(&4));
+}
+
+#[test]
+fn rows_define_columns_from_first_row() {
+    let table: HashTable<String, i32> =
+        serde_json::from_str(r#"[{"b": 1, "a": 2}, {"a": 4, "b": 3}]"#).unwrap();
+    assert_eq!(table.column_keys_in_order(), ["b", "a"]);
+    assert_eq!(table.rows_len(), 2);
+    assert_eq!(table.get("b", 1), Some(&3));
+    assert_eq!(table.get("a", 1), Some(&4));
+
+    let restored: HashTable<String, i32> =
+        serde_json::from_str(&serde_json::to_string(&table).unwrap()).unwrap();
+    assert_eq!(restored.rows_len(), 2);
+    assert_eq!(restored.get("a", 0), Some(&2));
+}
+
+#[test]
+fn invalid_rows_are_reported() {
+    let error = |json: &str| {
+        serde_json::from_str::<HashTable<String, i32>>(json)
+            .unwrap_err()
+            .to_string()
+    };
+    assert!(error(r#"[{"a": 1}, {"a": 2, "b": 3}]"#).contains("row 1 has unknown column `b`"));
+    assert!(error(r#"[{"a": 1, "b": 2}, {"b": 3}]"#).contains("row 1 is missing column `a`"));
+    assert!(error(r#"[{"a": 1}, {"a": 2, "a": 3}]"#).contains("row 1 has duplicate column `a`"));
+    assert!(error(r#"[{"a": 1, "a": 2}]"#).contains("row 0 has duplicate column `a`"));
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Deserialize)]
+struct Id(u32);
+
+#[derive(Debug, Deserialize)]
+#[serde(bound = "K: Hash + Eq + Deserialize<'de>")]
+struct Keys<K> {
+    #[serde(with = "hashtable_header_rows")]
+    table: HashTable<K, i32>,
+}
+
+#[test]
+fn invalid_keys_are_named_if_possible() {
+    let json = json!({ "table": { "columns": [7, 7], "rows": [] } });
+    let error = serde_json::from_value::<Keys<Id>>(json).unwrap_err();
+    assert!(error.to_string().contains("duplicate column `7`"));
+
+    let json = json!({ "table": { "columns": [[1, 2], [1, 2]], "rows": [] } });
+    let error = serde_json::from_value::<Keys<(u8, u8)>>(json).unwrap_err();
+    assert!(error.to_string().contains("duplicate column at index 1"));
+
+    let json = json!({ "table": { "columns": [7], "rows": [[1]] } });
+    let Keys { table } = serde_json::from_value::<Keys<Id>>(json).unwrap();
+    assert_eq!(table.get(&Id(7), 0), Some(&1));
+}
+
+#[derive(Debug, Deserialize)]
+struct Columns {
+    #[serde(deserialize_with = "crate::table::serde_impls::de::deserialize_hashtable_from_map")]
+    table: HashTable<String, i32>,
+}
+
+#[test]
+fn columns_map_is_validated() {
+    let Columns { table } =
+        serde_json::from_str(r#"{"table": {"x": [1, 2, 3], "y": [4, 5, 6]}}"#).unwrap();
+    assert_eq!(table.column_keys_in_order(), ["x", "y"]);
+    assert_eq!(table.get("y", 2), Some(&6));
+
+    let error = serde_json::from_str::<Columns>(r#"{"table": {"x": [1, 2], "y": [4]}}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("column `y` has 1 values, expected 2"));
+}
+
+#[test]
+fn rows_and_columns_serialize() {
+    let mut table = HashTable::from_column_keys_and_rows(
+        ["a", "b"].map(str::to_owned),
+        [[1, 2], [3, 4], [5, 6]],
+    );
+
+    let key = "b".to_owned();
+    let column = table.get_column(&key).unwrap();
+    assert_eq!(
+        serde_json::to_value(&column).unwrap(),
+        json!({ "key": "b", "values": [2, 4, 6] })
+    );
+
+    let row = table.get_row_mut(1).unwrap();
+    assert_eq!(
+        serde_json::to_value(&row).unwrap(),
+        json!({ "a": 3, "b": 4 })
+    );
+
+    let row = table.remove_row(1).unwrap();
+    assert_eq!(
+        serde_json::to_value(&row).unwrap(),
+        json!({ "a": 3, "b": 4 })
+    );
+
+    let column = table.remove_column("a").unwrap();
+    let json = serde_json::to_value(&column).unwrap();
+    assert_eq!(json, json!({ "key": "a", "values": [1, 5] }));
+    let column: HashTableColumnOwned<String, i32> = serde_json::from_value(json).unwrap();
+    assert_eq!(column.key(), "a");
+    assert_eq!(column.into_values(), [1, 5]);
+}
+
+#[test]
+fn hidden_computed_column_is_serialized_once() {
+    let mut table = crate::table::computed::ComputedHashTable::from(
+        HashTable::from_column_keys_and_rows(["a"], [[1]]),
+    );
+    table.add_computed_column("b", |row| row.get("a").unwrap() + 1);
+    table.table_mut().insert_column("b", [5]);
+    assert_eq!(
+        serde_json::to_string(&table)
+            .unwrap()
+            .matches("\"b\"")
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,70 @@
+use super::table;
+use crate::HashTable;
+
+fn rows(table: &HashTable<&'static str, i32>) -> Vec<(i32, i32)> {
+    table
+        .iter()
+        .map(|row| (*row.get(&"a").unwrap(), *row.get(&"b").unwrap()))
+        .collect()
+}
+
+#[test]
+fn distinct_keeps_first_rows() {
+    let table = table(["a", "b"], &[[1, 2], [3, 4], [1, 2], [5, 6], [3, 4]]);
+    assert_eq!(rows(&table.distinct()), [(1, 2), (3, 4), (5, 6)]);
+}
+
+#[test]
+fn distinct_by_columns() {
+    let table = table(["a", "b"], &[[1, 2], [1, 3], [2, 2], [2, 5]]);
+    assert_eq!(rows(&table.distinct_by(&["a"]).unwrap()), [(1, 2), (2, 2)]);
+    assert_eq!(
+        rows(&table.distinct_by(&["b"]).unwrap()),
+        [(1, 2), (1, 3), (2, 5)]
+    );
+    assert!(table.distinct_by(&["b", "missing"]).is_none());
+}
+
+#[test]
+fn duplicated_rows_by_columns() {
+    let table = table(["a", "b"], &[[1, 2], [1, 3], [1, 2], [2, 3]]);
+    assert_eq!(table.duplicated_rows(&["a", "b"]), Some(vec![2]));
+    assert_eq!(table.duplicated_rows(&["b"]), Some(vec![2, 3]));
+    assert_eq!(table.duplicated_rows::<&str, _>([]), Some(vec![1, 2, 3]));
+    assert_eq!(table.duplicated_rows(&["nmae"]), None);
+}
+
+#[test]
+fn union_matches_columns_by_key() {
+    let first = table(["a", "b"], &[[1, 2], [3, 4], [1, 2]]);
+    let second = table(["b", "a"], &[[4, 3], [6, 5], [6, 5]]);
+    let union = first.union(&second);
+    assert_eq!(union.column_keys_in_order(), [&"a", &"b"]);
+    assert_eq!(rows(&union), [(1, 2), (3, 4), (5, 6)]);
+}
+
+#[test]
+fn intersect_and_except() {
+    let first = table(["a", "b"], &[[1, 2], [3, 4], [5, 6], [3, 4]]);
+    let second = table(["b", "a"], &[[4, 3], [8, 7]]);
+    assert_eq!(rows(&first.intersect(&second)), [(3, 4)]);
+    assert_eq!(rows(&first.except(&second)), [(1, 2), (5, 6)]);
+}
+
+#[test]
+fn table_without_columns_is_identity() {
+    let first = table(["a", "b"], &[[1, 2], [3, 4], [1, 2]]);
+    let empty = HashTable::default();
+    assert_eq!(rows(&first.union(&empty)), [(1, 2), (3, 4)]);
+    assert_eq!(rows(&empty.union(&first)), [(1, 2), (3, 4)]);
+    assert_eq!(rows(&first.except(&empty)), [(1, 2), (3, 4)]);
+    assert_eq!(first.intersect(&empty).rows_len(), 0);
+    assert_eq!(empty.intersect(&first).rows_len(), 0);
+    assert_eq!(empty.except(&first).rows_len(), 0);
+}
+
+#[test]
+#[should_panic]
+fn union_with_other_columns() {
+    table(["a", "b"], &[[1, 2]]).union(&table(["a", "c"], &[[1, 2]]));
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::{
+    table::shared::{SharedHashTable, ROWS_PER_CHUNK},
+    HashTable,
+};
+
+fn numbers(rows: usize) -> SharedHashTable<&'static str, usize> {
+    HashTable::from_column_keys_and_rows(["n", "square"], (0..rows).map(|i| [i, i * i])).into()
+}
+
+#[test]
+fn clones_share_untouched_chunks() {
+    let original = numbers(ROWS_PER_CHUNK * 3);
+    let mut copy = original.clone();
+    assert!(Arc::ptr_eq(&original.chunks, &copy.chunks));
+    assert!(copy.is_row_shared(ROWS_PER_CHUNK + 1));
+
+    *copy.get_mut("n", ROWS_PER_CHUNK + 1).unwrap() = 0;
+    assert!(!Arc::ptr_eq(&original.chunks, &copy.chunks));
+    assert!(Arc::ptr_eq(&original.chunks[0], &copy.chunks[0]));
+    assert!(!Arc::ptr_eq(&original.chunks[1], &copy.chunks[1]));
+    assert!(Arc::ptr_eq(&original.chunks[2], &copy.chunks[2]));
+    assert!(copy.is_row_shared(0));
+    assert!(!copy.is_row_shared(ROWS_PER_CHUNK + 1));
+
+    assert_eq!(
+        original.get("n", ROWS_PER_CHUNK + 1),
+        Some(&(ROWS_PER_CHUNK + 1))
+    );
+    assert_eq!(copy.get("n", ROWS_PER_CHUNK + 1), Some(&0));
+}
+
+#[test]
+fn push_rows_across_chunks() {
+    let mut table = numbers(ROWS_PER_CHUNK - 1);
+    let copy = table.clone();
+    for i in ROWS_PER_CHUNK - 1..ROWS_PER_CHUNK + 2 {
+        table.push_row_with(|k| if *k == "n" { i } else { i * i });
+    }
+    assert_eq!(table.rows_len(), ROWS_PER_CHUNK + 2);
+    assert_eq!(copy.rows_len(), ROWS_PER_CHUNK - 1);
+    assert_eq!(table.chunks.len(), 2);
+    assert!(table
+        .iter()
+        .enumerate()
+        .all(|(i, row)| row.get("square") == Some(&(i * i))));
+    assert_eq!(table.iter().len(), ROWS_PER_CHUNK + 2);
+}
+
+#[test]
+fn column_changes_do_not_affect_copies() {
+    let mut table = numbers(10);
+    let copy = table.clone();
+    table.insert_column("cube", (0..10).map(|i| i * i * i));
+    assert_eq!(table.remove_column("square").unwrap()[3], 9);
+    assert_eq!(table.remove_column("square"), None);
+
+    assert_eq!(table.columns_len(), 2);
+    assert_eq!(table.get("cube", 2), Some(&8));
+    assert_eq!(copy.columns_len(), 2);
+    assert_eq!(copy.get("square", 2), Some(&4));
+    assert_eq!(copy.to_table().get("square", 9), Some(&81));
+
+    let mut row = table.get_row_mut(4).unwrap();
+    *row.get("n").unwrap() = 40;
+    assert_eq!(table.get("n", 4), Some(&40));
+}
@@ -0,0 +1,89 @@
+use crate::{stats::DESCRIBE_ROWS, HashTable};
+
+fn sample() -> HashTable<&'static str, f64> {
+    HashTable::from_column_keys_and_rows(
+        ["x", "y"],
+        [[1.0, 4.0], [2.0, f64::NAN], [3.0, 8.0], [4.0, 6.0]],
+    )
+}
+
+fn statistic(table: &HashTable<&'static str, f64>, column: &str, name: &str) -> f64 {
+    let row = DESCRIBE_ROWS.iter().position(|s| *s == name).unwrap();
+    *table.get(column, row).unwrap()
+}
+
+#[test]
+fn describe_columns() {
+    let description = sample().describe();
+    assert_eq!(description.column_keys_in_order(), [&"x", &"y"]);
+    assert_eq!(description.rows_len(), DESCRIBE_ROWS.len());
+    assert_eq!(statistic(&description, "x", "count"), 4.0);
+    assert_eq!(statistic(&description, "x", "mean"), 2.5);
+    assert!((statistic(&description, "x", "std") - 1.290_994).abs() < 1e-6);
+    assert_eq!(statistic(&description, "x", "25%"), 1.75);
+    assert_eq!(statistic(&description, "x", "75%"), 3.25);
+    assert_eq!(statistic(&description, "y", "count"), 3.0);
+    assert_eq!(statistic(&description, "y", "min"), 4.0);
+    assert_eq!(statistic(&description, "y", "50%"), 6.0);
+    assert_eq!(statistic(&description, "y", "max"), 8.0);
+}
+
+#[test]
+fn describe_empty_column() {
+    let description = HashTable::<_, i32>::with_columns(["x"]).describe();
+    assert_eq!(statistic(&description, "x", "count"), 0.0);
+    assert!(statistic(&description, "x", "mean").is_nan());
+    assert!(statistic(&description, "x", "max").is_nan());
+}
+
+#[test]
+fn column_aggregates() {
+    let table = sample();
+    assert_eq!(table.column_sum(&"x"), Some(10.0));
+    assert!(table.column_sum(&"y").unwrap().is_nan());
+    assert_eq!(table.column_mean(&"y"), Some(6.0));
+    assert_eq!(table.column_min_max(&"y"), Some((4.0, 8.0)));
+    assert_eq!(table.column_sum(&"z"), None);
+
+    let bytes = HashTable::from_column_keys_and_rows(["x", "y"], [[200u8, 1], [100, 2]]);
+    assert_eq!(bytes.column_sum(&"x"), None);
+    assert_eq!(bytes.column_sum(&"y"), Some(3));
+    assert_eq!(
+        HashTable::<_, u8>::with_columns(["x"]).column_sum(&"x"),
+        Some(0)
+    );
+    assert_eq!(
+        HashTable::<_, u8>::with_columns(["x"]).column_mean(&"x"),
+        None
+    );
+    assert_eq!(
+        HashTable::<_, u8>::with_columns(["x"]).column_min_max(&"x"),
+        None
+    );
+}
+
+#[test]
+fn histogram_bins() {
+    let table = HashTable::from_column_keys_and_rows(["x"], [0, 1, 2, 3, 9, 10].map(|v| [v]));
+    let histogram = table.column_histogram(&"x", 5).unwrap();
+    assert_eq!(histogram.len(), 5);
+    assert_eq!(histogram[0].start, 0.0);
+    assert_eq!(histogram[4].end, 10.0);
+    assert_eq!(
+        histogram.iter().map(|bin| bin.count).collect::<Vec<_>>(),
+        [2, 2, 0, 0, 2]
+    );
+
+    let constant = HashTable::from_column_keys_and_rows(["x"], [[5], [5]]);
+    assert_eq!(constant.column_histogram(&"x", 3).unwrap()[0].count, 2);
+}
+
+#[test]
+fn value_counts_order() {
+    let table = HashTable::from_column_keys_and_rows(["x"], [3, 1, 2, 1, 2, 1].map(|v| [v]));
+    assert_eq!(
+        table.value_counts(&"x"),
+        Some(vec![(&1, 3), (&2, 2), (&3, 1)])
+    );
+    assert_eq!(table.value_counts(&"y"), None);
+}
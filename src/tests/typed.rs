@@ -0,0 +1,126 @@
+use crate::{
+    table::typed::{ColumnKey, TypedHashTable},
+    HashTable,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Particle {
+    Position,
+    Velocity,
+    Mass,
+}
+
+impl ColumnKey for Particle {
+    const ALL: &'static [Self] = &[Self::Position, Self::Velocity, Self::Mass];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+fn particles() -> TypedHashTable<Particle, f64> {
+    let mut table = TypedHashTable::new();
+    for i in 0..3 {
+        table.push_row_with(|column| match column {
+            Particle::Position => i as f64,
+            Particle::Velocity => 1.0,
+            Particle::Mass => 2.0 * (i + 1) as f64,
+        });
+    }
+    table
+}
+
+#[test]
+fn typed_table_access() {
+    let mut table = particles();
+    assert_eq!(table.columns_len(), 3);
+    assert_eq!(table.rows_len(), 3);
+    assert_eq!(table.get(Particle::Mass, 2), Some(&6.0));
+    assert_eq!(table.get(Particle::Mass, 3), None);
+    table[(Particle::Velocity, 1)] = 5.0;
+    assert_eq!(
+        table
+            .get_column(Particle::Velocity)
+            .copied()
+            .collect::<Vec<_>>(),
+        [1.0, 5.0, 1.0]
+    );
+
+    let row = table.get_row(1).unwrap();
+    assert_eq!(row.values().copied().collect::<Vec<_>>(), [1.0, 5.0, 4.0]);
+    assert_eq!(
+        row.get_many([Particle::Mass, Particle::Position]),
+        [&4.0, &1.0]
+    );
+    assert_eq!(row.into_iter().next_back(), Some((Particle::Mass, &4.0)));
+    assert_eq!(row.to_hashmap()[&Particle::Velocity], 5.0);
+}
+
+#[test]
+fn typed_rows_mut() {
+    let mut table = particles();
+    for mut row in table.iter_mut() {
+        let [position, velocity] = row
+            .get_many_mut([Particle::Position, Particle::Velocity])
+            .unwrap();
+        *position += *velocity;
+    }
+    assert_eq!(table.get(Particle::Position, 2), Some(&3.0));
+    assert_eq!(table.iter_mut().len(), table.rows_len());
+    for (i, mut row) in (&mut table).into_iter().rev().enumerate() {
+        *row.get(Particle::Velocity) = i as f64;
+    }
+    assert_eq!(table.get(Particle::Velocity, 0), Some(&2.0));
+    let mut row = table.get_row_mut(0).unwrap();
+    assert!(row.get_many_mut([Particle::Mass, Particle::Mass]).is_none());
+    *row.get(Particle::Mass) = 0.0;
+    assert_eq!(table.get(Particle::Mass, 0), Some(&0.0));
+}
+
+#[test]
+fn typed_remove_and_retain() {
+    let mut table = particles();
+    assert_eq!(table.remove_row(0), Some(vec![0.0, 1.0, 2.0]));
+    assert_eq!(table.remove_row(5), None);
+    table.retain(|row| *row.get(Particle::Mass) > 5.0);
+    assert_eq!(table.rows_len(), 1);
+    assert_eq!(table.get(Particle::Position, 0), Some(&2.0));
+}
+
+#[test]
+fn convert_to_and_from_hash_table() {
+    let table: HashTable<_, _> = particles().into();
+    assert_eq!(table.get(&Particle::Mass, 1), Some(&4.0));
+    assert_eq!(
+        table.column_keys_in_order(),
+        [&Particle::Position, &Particle::Velocity, &Particle::Mass]
+    );
+
+    let reordered = table.select([&Particle::Mass, &Particle::Position, &Particle::Velocity]);
+    let typed = TypedHashTable::try_from(reordered).unwrap();
+    assert_eq!(typed, particles());
+
+    let partial = table.select([&Particle::Mass]);
+    assert!(TypedHashTable::<Particle, f64>::try_from(partial).is_err());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "doesn't match the position")]
+fn misordered_column_keys_are_caught() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Misordered {
+        First,
+        Second,
+    }
+
+    impl ColumnKey for Misordered {
+        const ALL: &'static [Self] = &[Self::Second, Self::First];
+
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    TypedHashTable::<Misordered, i32>::new();
+}
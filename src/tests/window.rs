@@ -0,0 +1,124 @@
+use crate::HashTable;
+
+fn prices() -> HashTable<&'static str, f64> {
+    HashTable::from_column_keys_and_rows(
+        ["time", "price"],
+        [[1.0, 10.0], [2.0, 12.0], [3.0, 9.0], [4.0, 18.0]],
+    )
+}
+
+#[test]
+fn rolling_windows() {
+    let table = prices();
+    let rolling = table.rolling(&"price", 3).unwrap();
+    assert_eq!(rolling.window(), 3);
+    let maxima = rolling.map(|window| window.iter().copied().fold(f64::MIN, f64::max));
+    assert_eq!(maxima, [None, None, Some(12.0), Some(18.0)]);
+
+    let short = table
+        .rolling(&"price", 10)
+        .unwrap()
+        .map(|window| window.len());
+    assert_eq!(short, [None, None, None, None]);
+    assert!(table.rolling(&"volume", 2).is_none());
+}
+
+#[test]
+#[should_panic]
+fn rolling_empty_window() {
+    prices().rolling(&"price", 0);
+}
+
+#[test]
+fn cumulative_operations() {
+    let table = HashTable::from_column_keys_and_rows(["x"], [[1], [2], [3], [4]]);
+    assert_eq!(
+        table.cumsum(&"x"),
+        Some(vec![Some(1), Some(3), Some(6), Some(10)])
+    );
+    assert_eq!(
+        table.cumprod(&"x"),
+        Some(vec![Some(1), Some(2), Some(6), Some(24)])
+    );
+    assert_eq!(table.cumsum(&"y"), None);
+    assert_eq!(
+        HashTable::<_, i32>::with_columns(["x"]).cumsum(&"x"),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn unsigned_overflow_is_none() {
+    let table = HashTable::from_column_keys_and_rows(["x"], [[200u8], [100], [1], [1]]);
+    assert_eq!(table.cumsum(&"x"), Some(vec![Some(200), None, None, None]));
+    assert_eq!(table.cumprod(&"x"), Some(vec![Some(200), None, None, None]));
+    assert_eq!(
+        table.diff_column(&"x"),
+        Some(vec![None, None, None, Some(0)])
+    );
+
+    let table = HashTable::from_column_keys_and_rows(["x"], [[5u32], [3], [7]]);
+    assert_eq!(table.diff_column(&"x"), Some(vec![None, None, Some(4)]));
+}
+
+#[test]
+fn differences_between_rows() {
+    let table = prices();
+    assert_eq!(
+        table.diff_column(&"price"),
+        Some(vec![None, Some(2.0), Some(-3.0), Some(9.0)])
+    );
+    let growth = HashTable::from_column_keys_and_rows(["x"], [[8], [10], [5], [20]]);
+    assert_eq!(
+        growth.pct_change(&"x"),
+        Some(vec![None, Some(0.25), Some(-0.5), Some(3.0)])
+    );
+    assert_eq!(
+        HashTable::<_, f64>::with_columns(["x"]).diff_column(&"x"),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn shift_values() {
+    let table = prices();
+    assert_eq!(table.shift(&"time", 0, 0.0), Some(vec![1.0, 2.0, 3.0, 4.0]));
+    assert_eq!(table.shift(&"time", 2, 0.0), Some(vec![0.0, 0.0, 1.0, 2.0]));
+    assert_eq!(
+        table.shift(&"time", -1, 0.0),
+        Some(vec![2.0, 3.0, 4.0, 0.0])
+    );
+    assert_eq!(table.shift(&"time", 9, 0.0), Some(vec![0.0; 4]));
+}
+
+#[test]
+fn replace_column_values_in_place() {
+    let mut table = prices();
+    let shifted = table.shift(&"price", 1, f64::NAN).unwrap();
+    let previous = table.replace_column_values(&"price", shifted).unwrap();
+    assert_eq!(previous, [10.0, 12.0, 9.0, 18.0]);
+    assert!(table.get(&"price", 0).unwrap().is_nan());
+    assert_eq!(table.get(&"price", 3), Some(&9.0));
+    assert_eq!(table.get(&"time", 3), Some(&4.0));
+    assert_eq!(table.replace_column_values(&"volume", []), None);
+}
+
+#[test]
+fn replace_column_values_length_mismatch() {
+    let mut table = prices();
+    for values in [vec![1.0], vec![1.0; 5]] {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            table.replace_column_values(&"price", values)
+        }));
+        assert!(result.is_err());
+        assert_eq!(
+            table
+                .get_column(&"price")
+                .unwrap()
+                .into_iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            [10.0, 12.0, 9.0, 18.0]
+        );
+    }
+}
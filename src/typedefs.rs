@@ -6,9 +6,11 @@ cfg_if! {
     if #[cfg(feature = "hashbrown")] {
         pub use hashbrown::HashMap;
         pub use hashbrown::hash_map::Keys;
+        pub use hashbrown::HashSet;
     } else {
         pub use std::collections::HashMap;
         pub use std::collections::hash_map::Keys;
+        pub use std::collections::HashSet;
     }
 }
 
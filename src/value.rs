@@ -0,0 +1,265 @@
+//! Dynamically typed table value
+
+use std::{cmp::Ordering, fmt};
+
+/// A dynamically typed value, used by tables whose columns hold values of different types.
+///
+/// This is what expressions evaluate to, see [`crate::expr`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum Value {
+    /// Absence of a value
+    #[default]
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl Value {
+    /// Name of the type of the value, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "bool",
+            Self::Int(_) => "int",
+            Self::Float(_) => "float",
+            Self::Str(_) => "string",
+        }
+    }
+
+    /// Whether the value is [`Value::Null`]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Numeric value as a float, if the value is a number
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(i) => Some(*i as f64),
+            Self::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// Compare values of compatible types.
+    ///
+    /// Integers and floats are compared numerically and exactly. Returns None if the types can't
+    /// be compared or a float is NaN.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Null, Self::Null) => Some(Ordering::Equal),
+            (Self::Bool(a), Self::Bool(b)) => Some(a.cmp(b)),
+            (Self::Int(a), Self::Int(b)) => Some(a.cmp(b)),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
+            (Self::Int(a), Self::Float(b)) => compare_int_float(*a, *b),
+            (Self::Float(a), Self::Int(b)) => compare_int_float(*b, *a).map(Ordering::reverse),
+            (Self::Str(a), Self::Str(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    /// Total order over all values, used for sorting.
+    ///
+    /// Values of different types are ordered as null < bool < number < string. Numbers are
+    /// compared numerically and exactly, so `-0.0` equals `0`. NaN is ordered after every other
+    /// number, or before them if its sign bit is set.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        fn rank(value: &Value) -> u8 {
+            match value {
+                Value::Null => 0,
+                Value::Bool(_) => 1,
+                Value::Int(_) | Value::Float(_) => 2,
+                Value::Str(_) => 3,
+            }
+        }
+        // Order of a NaN relative to any number that is not NaN
+        fn nan_order(nan: f64) -> Ordering {
+            if nan.is_sign_negative() {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }
+        match (self, other) {
+            (Self::Float(a), Self::Float(b)) => {
+                a.partial_cmp(b)
+                    .unwrap_or_else(|| match (a.is_nan(), b.is_nan()) {
+                        (true, false) => nan_order(*a),
+                        (false, true) => nan_order(*b).reverse(),
+                        _ => a.total_cmp(b),
+                    })
+            }
+            (Self::Int(a), Self::Float(b)) => {
+                compare_int_float(*a, *b).unwrap_or_else(|| nan_order(*b).reverse())
+            }
+            (Self::Float(a), Self::Int(b)) => {
+                compare_int_float(*b, *a).map_or_else(|| nan_order(*a), Ordering::reverse)
+            }
+            _ => self
+                .compare(other)
+                .unwrap_or_else(|| rank(self).cmp(&rank(other))),
+        }
+    }
+}
+
+/// Compare an integer with a float without rounding either of them.
+///
+/// Returns None if the float is NaN.
+fn compare_int_float(int: i64, float: f64) -> Option<Ordering> {
+    // -2^63 and 2^63 are exact floats, every finite float between them truncates to an `i64`
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    if float.is_nan() {
+        None
+    } else if float >= LIMIT {
+        Some(Ordering::Less)
+    } else if float < -LIMIT {
+        Some(Ordering::Greater)
+    } else {
+        Some(
+            int.cmp(&(float.trunc() as i64))
+                .then_with(|| 0.0.partial_cmp(&float.fract()).expect("Float is not NaN")),
+        )
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(i) => write!(f, "{i}"),
+            Self::Float(x) => write!(f, "{x:?}"),
+            Self::Str(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// Conversion of table values into a [`Value`], used to evaluate expressions over tables of any
+/// value type.
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+impl ToValue for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        self.as_ref().map_or(Value::Null, T::to_value)
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> Value {
+        Value::Str(self.to_owned())
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Str(self.clone())
+    }
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+macro_rules! impl_int_value {
+    ($($t:ty),*) => {$(
+        impl ToValue for $t {
+            fn to_value(&self) -> Value {
+                Value::Int((*self).into())
+            }
+        }
+
+        impl From<$t> for Value {
+            fn from(value: $t) -> Self {
+                Value::Int(value.into())
+            }
+        }
+    )*};
+}
+
+impl_int_value!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! impl_wide_int_value {
+    ($($t:ty),*) => {$(
+        /// Falls back to [`Value::Float`] if the value doesn't fit into an [`i64`]
+        impl ToValue for $t {
+            fn to_value(&self) -> Value {
+                i64::try_from(*self).map_or(Value::Float(*self as f64), Value::Int)
+            }
+        }
+
+        impl From<$t> for Value {
+            fn from(value: $t) -> Self {
+                value.to_value()
+            }
+        }
+    )*};
+}
+
+impl_wide_int_value!(u64, usize, isize);
+
+impl ToValue for f32 {
+    fn to_value(&self) -> Value {
+        Value::Float((*self).into())
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self) -> Value {
+        Value::Float(*self)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_owned())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}